@@ -111,7 +111,11 @@ fn testnet_genesis(
 		},
 		"sudo": {
 			// Assign network admin rights.
-			"key": Some(root_key),
+			"key": Some(root_key.clone()),
+		},
+		"staffMembership": {
+			// The network admin starts as the only store staff member.
+			"members": vec![root_key],
 		},
 	})
 }
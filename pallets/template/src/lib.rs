@@ -8,6 +8,10 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod migrations;
+
+pub mod runtime_api;
+
 pub mod weights;
 pub use weights::*;
 
@@ -17,8 +21,14 @@ pub mod pallet {
 
 	use super::*;
 	use frame_support::pallet_prelude::*;
+    use frame_support::{
+        sp_runtime::traits::{Hash, UniqueSaturatedInto},
+        storage::with_storage_layer,
+        traits::{fungible, tokens::{Fortitude, Precision, Preservation}, OnUnbalanced, UnixTime},
+        Hashable
+    };
     use frame_system::pallet_prelude::*;
-    use sp_std::vec::Vec;
+    use sp_std::{vec, vec::Vec};
 
     /// Enumeração que define as categorias de produtos.
     /// Pode ser um dos seguintes tipos: Eletrônicos, Alimentos, Roupas, Outros.
@@ -30,6 +40,11 @@ pub mod pallet {
         Misc
     }
 
+    impl Category {
+        /// Todas as categorias, na ordem da enumeração.
+        pub const ALL: [Category; 4] = [Category::Electronic, Category::Food, Category::Clothing, Category::Misc];
+    }
+
     /// Enumeração que define os métodos de pagamento aceitos.
     /// Pode ser um dos seguintes tipos: Cartão de Crédito, Cartão de Débito, Pix, Dinheiro.
     #[derive(Clone, Encode, Decode, TypeInfo, PartialEq, Debug, Eq, MaxEncodedLen)]
@@ -40,8 +55,106 @@ pub mod pallet {
         Money
     }
 
+    /// Enumeração que define como valores fracionários são arredondados.
+    /// Pode ser um dos seguintes tipos: para baixo, para cima ou para o mais próximo.
+    #[derive(Clone, Encode, Decode, Debug, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+    pub enum RoundingMode {
+        Down,
+        Up,
+        Nearest
+    }
+
+    impl RoundingMode {
+        /// Divide `numerator` por `denominator`, arredondando o resultado conforme o modo.
+        pub fn divide(&self, numerator: u128, denominator: u128) -> u128 {
+            let quotient = numerator / denominator;
+            let remainder = numerator % denominator;
+
+            let round_up = match self {
+                RoundingMode::Down => false,
+                RoundingMode::Up => remainder > 0,
+                RoundingMode::Nearest => remainder > 0 && remainder >= denominator - remainder
+            };
+
+            if round_up { quotient + 1 } else { quotient }
+        }
+    }
+
+    /// Enumeração que define como o limite de reposição de um produto é interpretado.
+    /// Pode ser um dos seguintes tipos: unidades em estoque ou dias de cobertura pelas vendas.
+    #[derive(Clone, Encode, Decode, Debug, TypeInfo, PartialEq, Eq, MaxEncodedLen, Default)]
+    pub enum ThresholdMode {
+        #[default]
+        Units,
+        DaysOfCover
+    }
+
+    /// Enumeração que define a unidade em que o estoque de um produto é contado, usada na exibição.
+    /// Pode ser um dos seguintes tipos: peça, quilograma, litro ou metro.
+    #[derive(Clone, Encode, Decode, Debug, TypeInfo, PartialEq, Eq, MaxEncodedLen, Default)]
+    pub enum StockUnitLabel {
+        #[default]
+        Piece,
+        Kilogram,
+        Liter,
+        Meter
+    }
+
+    /// Cursor opaco de paginação, contendo a chave bruta de armazenamento do último registro retornado.
+    /// Permite retomar a listagem exatamente de onde parou, mesmo que o mapa seja alterado entre as páginas.
+    #[derive(Clone, Encode, Decode, Debug, PartialEq, TypeInfo)]
+    pub struct StorageCursor(Vec<u8>);
+
+    /// Estatísticas agregadas da loja, usadas por painéis de acompanhamento.
+    /// Os valores saturam em caso de estouro.
+    #[derive(Clone, Encode, Decode, Debug, Default, PartialEq, TypeInfo)]
+    pub struct StoreStats {
+        /// Quantidade de produtos no catálogo ativo.
+        pub product_count: u32,
+        /// Quantidade de vendas registradas.
+        pub sale_count: u32,
+        /// Soma do estoque de todos os produtos, na escala de `StockUnit`.
+        pub total_units: u64,
+        /// Soma do valor em estoque de todos os produtos, com o estoque convertido de `StockUnit` em
+        /// unidades inteiras.
+        pub total_inventory_value: u64,
+        /// Quantidade de produtos abaixo do limite de reposição.
+        pub restock_needed: u32
+    }
+
+    /// Item de um recibo, unindo o item registrado na venda aos dados do produto.
+    #[derive(Clone, Encode, Decode, Debug, PartialEq, TypeInfo)]
+    pub struct ReceiptLine {
+        pub product_id: u64,
+        /// Nome do produto, vazio se o produto não existir mais.
+        pub name: Vec<u8>,
+        /// Preço de uma unidade inteira do produto cobrado na venda.
+        pub unit_price: u64,
+        /// Quantidade vendida, na escala de `StockUnit`.
+        pub amount: u64,
+        /// Diferença entre o valor ao preço unitário da venda e o valor cobrado.
+        pub discount: u64,
+        /// Valor cobrado pelo item.
+        pub line_total: u64,
+        /// Parcela do imposto da venda atribuída ao item.
+        pub tax: u64
+    }
+
+    /// Recibo detalhado de uma venda.
+    #[derive(Clone, Encode, Decode, Debug, PartialEq, TypeInfo)]
+    pub struct Receipt {
+        pub code: u64,
+        pub lines: Vec<ReceiptLine>,
+        /// Soma do valor cobrado pelos itens.
+        pub subtotal: u64,
+        pub tax: u64,
+        pub surcharge: u64,
+        /// Valor total da venda, incluindo a sobretaxa.
+        pub total: u64
+    }
+
     /// Estrutura que define uma data (dia, mês, ano).
-    #[derive(Clone, Encode, Decode, Debug, TypeInfo, Default, PartialEq, MaxEncodedLen)]
+    #[derive(Clone, Encode, Decode, Debug, TypeInfo, Default, PartialEq, Eq, MaxEncodedLen)]
     pub struct Date {
         pub day: u8,
         pub month: u8,
@@ -57,34 +170,235 @@ pub mod pallet {
             }
             Ok(Self { day, month, year })
         }
+
+        /// Converte um instante em segundos desde 01/01/1970 (UTC) na data correspondente.
+        pub fn from_unix_secs(secs: u64) -> Self {
+            // Algoritmo de conversão de dias para data civil do calendário gregoriano
+            let z = secs / 86_400 + 719_468;
+            let era = z / 146_097;
+            let doe = z - era * 146_097;
+            let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+            let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+            let mp = (5 * doy + 2) / 153;
+            let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+            let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u8;
+            let year = yoe + era * 400 + u64::from(month <= 2);
+
+            Self { day, month, year }
+        }
     }
 
-    /// Estrutura que representa um item de venda, incluindo o ID do produto e a quantidade vendida.
-    #[derive(Clone, Encode, Decode, Debug, PartialEq, TypeInfo)]
+    /// Datas são ordenadas cronologicamente: por ano, depois mês e dia.
+    impl Ord for Date {
+        fn cmp(&self, other: &Self) -> sp_std::cmp::Ordering {
+            (self.year, self.month, self.day).cmp(&(other.year, other.month, other.day))
+        }
+    }
+
+    impl PartialOrd for Date {
+        fn partial_cmp(&self, other: &Self) -> Option<sp_std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    /// Chave de um atributo de produto (ex.: "cor"), com até 32 bytes.
+    pub type AttributeKey = BoundedVec<u8, ConstU32<32>>;
+
+    /// Valor de um atributo de produto (ex.: "azul"), com até 64 bytes.
+    pub type AttributeValue = BoundedVec<u8, ConstU32<64>>;
+
+    /// Atributos chave-valor de um produto, limitados a `MaxAttributes`.
+    pub type Attributes<MaxAttributes> = BoundedVec<(AttributeKey, AttributeValue), MaxAttributes>;
+
+    /// Estrutura que representa um item de venda, incluindo o ID do produto, a quantidade vendida
+    /// e um desconto percentual opcional aplicado apenas a esse item.
+    #[derive(Clone, Encode, Decode, Debug, PartialEq, TypeInfo, MaxEncodedLen)]
     pub struct ItemSale {
         pub product_id: u64,
-        pub amount: u64
+        pub amount: u64,
+        pub discount_percent: Option<u8>
+    }
+
+    /// Estrutura que representa um item registrado em uma venda, com o valor efetivo após descontos.
+    #[derive(Clone, Encode, Decode, Debug, PartialEq, TypeInfo, MaxEncodedLen)]
+    pub struct SaleLine {
+        pub product_id: u64,
+        pub amount: u64,
+        /// Preço unitário cobrado na venda, já considerando o preço relâmpago e o preço de membro.
+        pub unit_price: u64,
+        /// Desconto aplicado ao item: o maior entre o informado e o da promoção vigente no registro da venda.
+        pub discount_percent: Option<u8>,
+        /// Piso de preço unitário do produto no momento da venda.
+        pub price_floor: Option<u64>,
+        pub value: u64,
+        /// Categoria do produto no momento da venda.
+        pub category: Category,
+        /// Parcela do imposto da venda atribuída ao item.
+        pub tax: u64,
+        /// Parcela do valor do item paga ao consignante, fora da receita da loja.
+        pub consigned: u64
+    }
+
+    impl SaleLine {
+        /// Valor do item que fica com a loja, sem a parcela do consignante.
+        pub fn store_value(&self) -> u64 {
+            self.value.saturating_sub(self.consigned)
+        }
+    }
+
+    /// Resultado da validação dos itens de uma venda, calculado antes de qualquer escrita.
+    #[derive(DefaultNoBound)]
+    struct CartCheck<T: Config> {
+        /// Valor total dos itens, após descontos.
+        total_value: u64,
+        total_tax: u64,
+        /// Soma do imposto dos itens sem arredondamento, na escala de 1/10.000.
+        scaled_tax: u128,
+        lines: Vec<SaleLine>,
+        /// Produtos envolvidos na venda, com o estoque já descontado.
+        updated: Vec<(u64, ProductOf<T>)>,
+        /// Déficits de produtos vendidos além do estoque.
+        backorders: Vec<(u64, u64)>,
+        /// Estoque retirado de cada categoria.
+        category_stock_taken: Vec<(Category, u64)>,
+        /// Custo devido a cada consignante pelos itens em consignação.
+        consignments: Vec<(T::AccountId, u64)>,
+        /// Produtos cujo valor foi elevado ao piso de preço.
+        floored: Vec<u64>
+    }
+
+    /// Item rejeitado na validação de uma venda, com os dados usados no diagnóstico de `validate_cart`.
+    struct CartRejection {
+        product_id: u64,
+        error: DispatchError,
+        /// Quantidade pedida no item.
+        requested: u64,
+        /// Estoque do produto ainda disponível para o item, descontados os itens anteriores da venda.
+        available: u64,
+        /// Total acumulado dos itens anteriores ao rejeitado.
+        running_total: u64
     }
 
     /// Estrutura que define as propriedades de um produto.
-    #[derive(Clone, Debug, Encode, Decode, PartialEq, TypeInfo)]
-    pub struct Product {
+    /// A quantidade de atributos é limitada por `MaxAttributes`, normalmente o `Config::MaxAttributes` do pallet.
+    #[derive(CloneNoBound, DebugNoBound, Encode, Decode, PartialEqNoBound, TypeInfo)]
+    #[scale_info(skip_type_params(MaxAttributes))]
+    pub struct Product<MaxAttributes: Get<u32>> {
         pub name: Vec<u8>,
-        id: u64,
+        pub(crate) id: u64,
+        /// Estoque em frações de unidade, na escala definida por `StockUnit`.
         pub stock: u64,
+        /// Preço de uma unidade inteira do produto.
         pub price: u64,
         pub amount_to_restock: u64,
+        /// Nível de estoque em que o produto passa a precisar de reposição, em unidades no modo `Units`
+        /// e em dias de cobertura no modo `DaysOfCover`.
+        /// Começa igual a `amount_to_restock`, que segue sendo a quantidade reposta.
+        pub reorder_point: u64,
         pub restock_date: Date,
-        pub category: Category
+        pub category: Category,
+        /// Hash de metadados externos (ex.: imagens no IPFS) associados ao produto.
+        pub metadata_hash: Option<[u8; 32]>,
+        /// Nível de estoque acima do qual o alerta de reposição é encerrado.
+        /// Quando ausente, o próprio limite de reposição é usado.
+        pub restock_clear_level: Option<u64>,
+        /// Categoria tributária do produto, usada para buscar a alíquota em `TaxRates`.
+        pub tax_category: u8,
+        /// Define se `amount_to_restock` é uma quantidade de unidades ou de dias de cobertura.
+        pub threshold_mode: ThresholdMode,
+        /// Quantidade mínima de unidades por item de venda.
+        pub min_order_qty: Option<u64>,
+        /// Quantidade máxima de unidades por item de venda.
+        pub max_order_qty: Option<u64>,
+        /// Preço de uma unidade inteira para membros, quando diferente do preço padrão.
+        pub member_price: Option<u64>,
+        /// Promoção do produto: data de início, data de fim (inclusive) e percentual de desconto.
+        pub promo: Option<(Date, Date, u8)>,
+        /// Atributos livres do produto, como tamanho, cor ou voltagem.
+        pub attributes: Attributes<MaxAttributes>,
+        /// Custo de uma unidade inteira do produto, usado para verificar a margem mínima.
+        pub cost_price: Option<u64>,
+        /// Unidade em que o estoque é contado. Produtos vendidos por peça não admitem frações de unidade.
+        pub unit: StockUnitLabel,
+        /// Preço mínimo de uma unidade inteira nas vendas, após todos os descontos.
+        pub price_floor: Option<u64>
+    }
+
+    impl<MaxAttributes: Get<u32>> Product<MaxAttributes> {
+        /// Verifica se o nível de encerramento do alerta não está abaixo do ponto de reposição.
+        fn has_valid_clear_level(&self) -> bool {
+            self.restock_clear_level.map_or(true, |level| level >= self.reorder_point)
+        }
+
+        /// Verifica se a promoção começa antes de terminar e tem desconto de no máximo 100%.
+        fn has_valid_promo(&self) -> bool {
+            self.promo.as_ref().map_or(true, |(start, end, percent)| start <= end && *percent <= 100)
+        }
+
+        /// Retorna o percentual de desconto da promoção vigente na data informada, se houver.
+        pub fn promo_discount(&self, date: &Date) -> Option<u8> {
+            match &self.promo {
+                Some((start, end, percent)) if start <= date && date <= end => Some(*percent),
+                _ => None
+            }
+        }
+
+        /// Verifica se o preço para membros não é maior que o preço padrão.
+        fn has_valid_member_price(&self) -> bool {
+            self.member_price.map_or(true, |member_price| member_price <= self.price)
+        }
+
+        /// Verifica se o piso de preço não é maior que o preço padrão.
+        fn has_valid_price_floor(&self) -> bool {
+            self.price_floor.map_or(true, |floor| floor <= self.price)
+        }
+
+        /// Verifica se a margem `(preço - custo) / preço` atinge o mínimo, em pontos-base.
+        /// Produtos sem custo informado não são verificados.
+        fn has_min_margin(&self, min_margin_basis_points: u32) -> bool {
+            match self.cost_price {
+                Some(cost_price) if min_margin_basis_points > 0 => {
+                    let margin = (self.price as u128).saturating_sub(cost_price as u128) * 10_000;
+                    cost_price <= self.price && margin >= min_margin_basis_points as u128 * self.price as u128
+                }
+                _ => true
+            }
+        }
+
+        /// Verifica se a quantidade, na escala de `stock_unit`, é válida para a unidade do produto.
+        /// Produtos vendidos por peça exigem unidades inteiras.
+        fn accepts_quantity(&self, quantity: u64, stock_unit: u64) -> bool {
+            self.unit != StockUnitLabel::Piece || stock_unit <= 1 || quantity % stock_unit == 0
+        }
+
+        /// Verifica se a quantidade mínima por venda não é maior que a máxima.
+        fn has_valid_order_limits(&self) -> bool {
+            match (self.min_order_qty, self.max_order_qty) {
+                (Some(min), Some(max)) => min <= max,
+                _ => true
+            }
+        }
     }
 
-    impl MaxEncodedLen for Product {
+    impl<MaxAttributes: Get<u32>> MaxEncodedLen for Product<MaxAttributes> {
         fn max_encoded_len() -> usize {
             let max_name_length = 256;
             let max_date_length = Date::max_encoded_len();
             let max_category_length = Category::max_encoded_len();
+            let max_metadata_hash_length = Option::<[u8; 32]>::max_encoded_len();
+            let max_clear_level_length = Option::<u64>::max_encoded_len();
+            let max_tax_category_length = u8::max_encoded_len();
+            let max_threshold_mode_length = ThresholdMode::max_encoded_len();
+            let max_order_qty_length = Option::<u64>::max_encoded_len() * 2;
+            let max_reorder_point_length = u64::max_encoded_len();
+            let max_member_price_length = Option::<u64>::max_encoded_len();
+            let max_promo_length = Option::<(Date, Date, u8)>::max_encoded_len();
+            let max_attributes_length = Attributes::<MaxAttributes>::max_encoded_len();
+            let max_cost_price_length = Option::<u64>::max_encoded_len();
+            let max_unit_length = StockUnitLabel::max_encoded_len();
+            let max_price_floor_length = Option::<u64>::max_encoded_len();
     
-            max_name_length + max_date_length + max_category_length + 32
+            max_name_length + max_date_length + max_category_length + max_metadata_hash_length + max_clear_level_length + max_tax_category_length + max_threshold_mode_length + max_order_qty_length + max_reorder_point_length + max_member_price_length + max_promo_length + max_attributes_length + max_cost_price_length + max_unit_length + max_price_floor_length + 32
         }
     }
 
@@ -92,11 +406,34 @@ pub mod pallet {
     #[derive(Clone, Debug, Encode, Decode, PartialEq, TypeInfo)]
     pub struct Sale {
         pub seller: Vec<u8>,
-        code: u64,
+        pub(crate) code: u64,
         pub products: Vec<u64>,
         pub value: u64,
-        date: Date,
-        pub payment_method: PaymentMethod
+        pub(crate) date: Date,
+        pub payment_method: PaymentMethod,
+        pub lines: Vec<SaleLine>,
+        /// Cartão-presente usado na venda e o valor debitado dele.
+        pub gift_card: Option<([u8; 16], u64)>,
+        /// Moeda de exibição da venda e o valor total convertido para ela.
+        pub display_currency: Option<([u8; 3], u64)>,
+        /// Comissão devida ao vendedor pela venda.
+        pub commission: u64,
+        /// Imposto total da venda, somando o imposto de cada item.
+        pub tax: u64,
+        /// Valor líquido dos itens, sem o imposto nem a sobretaxa.
+        pub net: u64,
+        /// Sobretaxa do método de pagamento, já incluída no valor total.
+        pub surcharge: u64,
+        /// Divisão do pagamento entre os métodos usados, somando o valor total.
+        pub payments: Vec<(PaymentMethod, u64)>
+    }
+
+    impl Sale {
+        /// Valor da venda que fica com a loja, sem as parcelas pagas aos consignantes.
+        pub fn store_value(&self) -> u64 {
+            let consigned = self.lines.iter().fold(0u64, |total, line| total.saturating_add(line.consigned));
+            self.value.saturating_sub(consigned)
+        }
     }
 
     impl MaxEncodedLen for Sale {
@@ -104,16 +441,39 @@ pub mod pallet {
             let seller_length = 256;
             let date_length = Date::max_encoded_len();
             let payment_method_length = PaymentMethod::max_encoded_len();
+            // Mesma estimativa de 12 itens usada para a lista de produtos
+            let lines_length = SaleLine::max_encoded_len() * 12;
+            let gift_card_length = Option::<([u8; 16], u64)>::max_encoded_len();
+            let display_currency_length = Option::<([u8; 3], u64)>::max_encoded_len();
+            let commission_length = u64::max_encoded_len();
+            let tax_length = u64::max_encoded_len();
+            let net_length = u64::max_encoded_len();
+            let surcharge_length = u64::max_encoded_len();
+            // No máximo uma parcela por método de pagamento
+            let payments_length = 1 + (PaymentMethod::max_encoded_len() + u64::max_encoded_len()) * 4;
         
-            seller_length + date_length + payment_method_length + 96
+            seller_length + date_length + payment_method_length + lines_length + gift_card_length + display_currency_length + commission_length + tax_length + net_length + surcharge_length + payments_length + 96
         }
     }
 
-    pub fn total_max_encoded_len() -> usize {
-        Sale::max_encoded_len() + Product::max_encoded_len()
+    /// Tipo do saldo da moeda configurada.
+    pub type BalanceOf<T> = <<T as Config>::Currency as fungible::Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+
+    /// Valor retirado de uma conta e ainda não destinado.
+    pub type CreditOf<T> = fungible::Credit<<T as frame_system::Config>::AccountId, <T as Config>::Currency>;
+
+    /// Produto com os atributos limitados pela configuração do pallet.
+    pub type ProductOf<T> = Product<<T as Config>::MaxAttributes>;
+
+    pub fn total_max_encoded_len<T: Config>() -> usize {
+        Sale::max_encoded_len() + ProductOf::<T>::max_encoded_len()
     }
 
+    /// Versão atual do armazenamento do pallet.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::config]
@@ -123,17 +483,310 @@ pub mod pallet {
         
         /// O tipo que define os pesos necessários para as funções do pallet.
         type WeightInfo: WeightInfo;
+
+        /// Quantidade máxima permitida para a reposição de um produto.
+        #[pallet::constant]
+        type MaxRestockAmount: Get<u64>;
+
+        /// Define se as chamadas de listagem emitem os registros completos nos eventos.
+        /// Quando falso, apenas a quantidade de registros é emitida e os dados devem ser obtidos pela API de runtime.
+        #[pallet::constant]
+        type EmitListingPayloads: Get<bool>;
+
+        /// Origem autorizada a executar as funções administrativas do pallet.
+        type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origem dos funcionários, autorizada a alterar o catálogo e as vendas registradas.
+        type StaffOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+
+        /// Origem dos clientes, autorizada a registrar vendas.
+        type CustomerOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+
+        /// Quantidade máxima de registros mantidos no histórico de preços de cada produto.
+        #[pallet::constant]
+        type MaxPriceHistory: Get<u32>;
+
+        /// Idade máxima, em blocos, de um registro no histórico de preços.
+        /// Registros mais antigos são descartados sempre que um novo preço é registrado.
+        #[pallet::constant]
+        type PriceHistoryMaxAge: Get<BlockNumberFor<Self>>;
+
+        /// Quantidade máxima de registros retornados por uma chamada de listagem.
+        #[pallet::constant]
+        type MaxPageSize: Get<u32>;
+
+        /// Quantidade de registros retornados por uma chamada de listagem quando o limite informado é zero.
+        #[pallet::constant]
+        type DefaultPageSize: Get<u32>;
+
+        /// Quantidade de blocos em um dia, usada para calcular a velocidade de vendas.
+        #[pallet::constant]
+        type BlocksPerDay: Get<BlockNumberFor<Self>>;
+
+        /// Incremento ao qual os preços calculados (após descontos e reajustes) são arredondados.
+        /// O valor 1 desativa o arredondamento.
+        #[pallet::constant]
+        type PriceIncrement: Get<u64>;
+
+        /// Moeda usada para cobrar a taxa de cada venda.
+        type Currency: fungible::Balanced<Self::AccountId>;
+
+        /// Taxa cobrada do comprador em cada venda. O valor zero desativa a cobrança.
+        #[pallet::constant]
+        type SaleFee: Get<BalanceOf<Self>>;
+
+        /// Destino da taxa cobrada. Usando `()`, o valor é queimado.
+        type FeeDestination: OnUnbalanced<CreditOf<Self>>;
+
+        /// Quantidade de frações que compõem uma unidade inteira de produto (ex.: 1000 para gramas).
+        /// O estoque e as quantidades vendidas são expressos nessa escala; o valor 1 mantém unidades inteiras.
+        #[pallet::constant]
+        type StockUnit: Get<u64>;
+
+        /// Fonte do horário on-chain, usada para obter a data atual.
+        type TimeProvider: UnixTime;
+
+        /// Define se a data de reposição informada ao criar ou atualizar um produto não pode estar no passado.
+        #[pallet::constant]
+        type EnforceFutureRestock: Get<bool>;
+
+        /// Define se os IDs de produtos removidos são reutilizados por novos produtos.
+        /// Quando falso, os IDs são sempre crescentes.
+        #[pallet::constant]
+        type RecycleIds: Get<bool>;
+
+        /// Quantidade máxima de IDs livres guardados para reutilização.
+        #[pallet::constant]
+        type MaxFreeProductIds: Get<u32>;
+
+        /// Quantidade máxima de componentes em um kit.
+        #[pallet::constant]
+        type MaxBundleSize: Get<u32>;
+
+        /// Comissão do vendedor em pontos-base sobre o valor total da venda.
+        #[pallet::constant]
+        type CommissionBasisPoints: Get<u32>;
+
+        /// Menor ano aceito nas datas informadas.
+        #[pallet::constant]
+        type MinYear: Get<u64>;
+
+        /// Maior ano aceito nas datas informadas.
+        #[pallet::constant]
+        type MaxYear: Get<u64>;
+
+        /// Quantidade máxima de produtos e de vendas retornados em uma exportação.
+        #[pallet::constant]
+        type MaxSnapshotSize: Get<u32>;
+
+        /// Define se vendas podem ultrapassar o estoque disponível, registrando o déficit como encomenda.
+        #[pallet::constant]
+        type AllowOversell: Get<bool>;
+
+        /// Alíquota padrão, em pontos-base, para categorias tributárias sem alíquota cadastrada.
+        #[pallet::constant]
+        type DefaultTaxRate: Get<u32>;
+
+        /// Define se os códigos de venda são derivados do conteúdo da venda (vendedor, itens e bloco)
+        /// em vez de sequenciais. Vendas idênticas no mesmo bloco geram o mesmo código e são rejeitadas.
+        #[pallet::constant]
+        type HashedSaleCodes: Get<bool>;
+
+        /// Quantidade de blocos que um produto pode permanecer abaixo do limite de reposição
+        /// antes de ser sinalizado como violação do prazo de reposição.
+        #[pallet::constant]
+        type RestockSlaBlocks: Get<BlockNumberFor<Self>>;
+
+        /// Quantidade máxima de alertas de reposição verificados quanto ao prazo em um mesmo bloco.
+        #[pallet::constant]
+        type MaxSlaChecksPerBlock: Get<u32>;
+
+        /// Quantidade de blocos que uma venda é mantida antes de ser removida.
+        /// Zero desativa a remoção de vendas antigas.
+        #[pallet::constant]
+        type SaleRetentionBlocks: Get<BlockNumberFor<Self>>;
+
+        /// Quantidade máxima de vendas antigas removidas em um mesmo bloco.
+        #[pallet::constant]
+        type MaxSalesPrunedPerBlock: Get<u32>;
+
+        /// Quantidade de um produto em um único item de venda a partir da qual a venda é sinalizada.
+        /// Zero desativa a sinalização.
+        #[pallet::constant]
+        type BulkQtyThreshold: Get<u64>;
+
+        /// Incremento aplicado ao avançar os contadores de IDs de produtos e códigos de vendas.
+        /// Deve ser maior que zero, ou os IDs seriam repetidos.
+        #[pallet::constant]
+        type IdStep: Get<u64>;
+
+        /// Quantidade de blocos após a remoção de um produto em que a remoção ainda pode ser desfeita.
+        #[pallet::constant]
+        type UndoWindow: Get<BlockNumberFor<Self>>;
+
+        /// Quantidade de blocos após o registro de uma venda em que seus itens ainda podem ser corrigidos.
+        #[pallet::constant]
+        type SaleEditWindow: Get<BlockNumberFor<Self>>;
+
+        /// Define se atualizações sem nenhum campo informado são rejeitadas.
+        /// Quando falso, elas são aceitas sem alterar nada.
+        #[pallet::constant]
+        type RejectEmptyUpdates: Get<bool>;
+
+        /// Estoque mínimo para que um produto apareça nas listagens e possa ser vendido.
+        /// Zero mantém todos os produtos disponíveis.
+        #[pallet::constant]
+        type MinListableStock: Get<u64>;
+
+        /// Margem mínima exigida sobre o preço dos produtos com custo informado, em pontos-base.
+        /// Zero desativa a verificação.
+        #[pallet::constant]
+        type MinMarginBasisPoints: Get<u32>;
+
+        /// Janela, em blocos, considerada no cálculo da velocidade de vendas dos produtos.
+        #[pallet::constant]
+        type VelocityWindow: Get<BlockNumberFor<Self>>;
+
+        /// Quantidade máxima de amostras de vendas guardadas por produto dentro da janela.
+        #[pallet::constant]
+        type MaxVelocitySamples: Get<u32>;
+
+        /// Quantidade de blocos após o registro de uma venda em que ela ainda pode ser estornada.
+        /// Zero (ou o valor máximo) permite estornar a qualquer momento.
+        #[pallet::constant]
+        type RefundWindowBlocks: Get<BlockNumberFor<Self>>;
+
+        /// Valor em estoque (`estoque * preço`) acima do qual a remoção de um produto precisa ser
+        /// aprovada por um administrador. Zero desativa a aprovação.
+        #[pallet::constant]
+        type DeletionApprovalThreshold: Get<u64>;
+
+        /// Define se o imposto da venda é arredondado uma única vez sobre o total dos itens e depois
+        /// distribuído entre eles, em vez de arredondado separadamente em cada item.
+        #[pallet::constant]
+        type TaxOnSaleTotal: Get<bool>;
+
+        /// Quantidade máxima de atributos por produto.
+        #[pallet::constant]
+        type MaxAttributes: Get<u32>;
+
+        /// Quantidade máxima de preços relâmpago que podem terminar em um mesmo bloco.
+        #[pallet::constant]
+        type MaxFlashExpiries: Get<u32>;
+
+        /// Quantidade máxima de códigos de venda consultados por `recent_sales`, incluindo os de vendas removidas.
+        #[pallet::constant]
+        type MaxRecentSalesScan: Get<u32>;
+
+        /// Quantidade máxima de vendas que podem ser registradas em um mesmo bloco.
+        #[pallet::constant]
+        type MaxSalesPerBlock: Get<u32>;
+
+        /// Modo de arredondamento usado em conversões e cálculos fracionários.
+        #[pallet::constant]
+        type Rounding: Get<RoundingMode>;
     }
 
     /// Mapeamento de produtos armazenados, usando o ID do produto como chave.
     #[pallet::storage]
     #[pallet::getter(fn products)]
-    pub type Products<T> = StorageMap<_, Blake2_128Concat, u64, Product, OptionQuery>;
+    pub type Products<T: Config> = CountedStorageMap<_, Blake2_128Concat, u64, ProductOf<T>, OptionQuery>;
+
+    /// IDs de produtos removidos disponíveis para reutilização, quando `RecycleIds` está habilitado.
+    #[pallet::storage]
+    #[pallet::getter(fn free_product_ids)]
+    pub type FreeProductIds<T: Config> = StorageValue<_, BoundedVec<u64, T::MaxFreeProductIds>, ValueQuery>;
+
+    /// Índice de produtos por categoria, usado para consultas sem percorrer todo o catálogo.
+    #[pallet::storage]
+    pub type ProductsByCategory<T> = StorageDoubleMap<_, Blake2_128Concat, Category, Blake2_128Concat, u64, (), OptionQuery>;
+
+    /// Quantidade de produtos ativos em cada categoria, mantida junto com `ProductsByCategory`.
+    #[pallet::storage]
+    #[pallet::getter(fn category_counts)]
+    pub type CategoryCounts<T> = StorageMap<_, Blake2_128Concat, Category, u32, ValueQuery>;
+
+    /// Estado do alerta de reposição de cada produto, verdadeiro enquanto o alerta está ativo.
+    #[pallet::storage]
+    #[pallet::getter(fn alert_state)]
+    pub type AlertState<T> = StorageMap<_, Blake2_128Concat, u64, bool, ValueQuery>;
+
+    /// Bloco em que cada produto entrou em alerta de reposição, removido quando o alerta é encerrado.
+    #[pallet::storage]
+    #[pallet::getter(fn low_stock_since)]
+    pub type LowStockSince<T: Config> = StorageMap<_, Blake2_128Concat, u64, BlockNumberFor<T>, OptionQuery>;
+
+    /// Fila dos alertas de reposição em ordem de abertura, usada para verificar o prazo de reposição
+    /// sem percorrer `LowStockSince`. Cada posição guarda o bloco de abertura e o ID do produto.
+    #[pallet::storage]
+    pub type RestockSlaQueue<T: Config> = StorageMap<_, Blake2_128Concat, u64, (BlockNumberFor<T>, u64), OptionQuery>;
+
+    /// Posição do alerta mais antigo e próxima posição livre da fila `RestockSlaQueue`.
+    #[pallet::storage]
+    pub type RestockSlaQueueBounds<T> = StorageValue<_, (u64, u64), ValueQuery>;
+
+    /// Histórico de preços de cada produto, com o bloco em que cada preço passou a valer.
+    #[pallet::storage]
+    #[pallet::getter(fn price_history)]
+    pub type PriceHistory<T: Config> = StorageMap<_, Blake2_128Concat, u64, BoundedVec<(BlockNumberFor<T>, u64), T::MaxPriceHistory>, ValueQuery>;
 
     /// Mapeamento de vendas registradas, usando o código da venda como chave.
     #[pallet::storage]
     #[pallet::getter(fn sales)]
-    pub type Sales<T> = StorageMap<_, Blake2_128Concat, u64, Sale, OptionQuery>;
+    pub type Sales<T> = CountedStorageMap<_, Blake2_128Concat, u64, Sale, OptionQuery>;
+
+    /// Conta responsável por cada produto.
+    #[pallet::storage]
+    #[pallet::getter(fn product_owner)]
+    pub type ProductOwners<T: Config> = StorageMap<_, Blake2_128Concat, u64, T::AccountId, OptionQuery>;
+
+    /// Consignante de cada produto em consignação, que recebe o custo das unidades vendidas.
+    #[pallet::storage]
+    #[pallet::getter(fn consignor)]
+    pub type Consignors<T: Config> = StorageMap<_, Blake2_128Concat, u64, T::AccountId, OptionQuery>;
+
+    /// Preço relâmpago de cada produto e o último bloco em que ele vale.
+    #[pallet::storage]
+    pub type FlashPrices<T: Config> = StorageMap<_, Blake2_128Concat, u64, (u64, BlockNumberFor<T>), OptionQuery>;
+
+    /// Produtos cujo preço relâmpago expira em cada bloco (o bloco seguinte ao último em que ele vale).
+    #[pallet::storage]
+    pub type FlashExpiries<T: Config> = StorageMap<_, Blake2_128Concat, BlockNumberFor<T>, BoundedVec<u64, T::MaxFlashExpiries>, ValueQuery>;
+
+    /// Índice dos produtos de cada conta.
+    #[pallet::storage]
+    pub type OwnerProducts<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, u64, (), OptionQuery>;
+
+    /// Versão do catálogo, incrementada a cada alteração de produtos.
+    /// Permite que clientes detectem mudanças consultando um único valor.
+    #[pallet::storage]
+    #[pallet::getter(fn catalog_version)]
+    pub type CatalogVersion<T> = StorageValue<_, u64, ValueQuery>;
+
+    /// Versão das vendas, incrementada a cada alteração de vendas.
+    #[pallet::storage]
+    #[pallet::getter(fn sales_version)]
+    pub type SalesVersion<T> = StorageValue<_, u64, ValueQuery>;
+
+    /// Último produto removido, o seu dono e o bloco da remoção, mantidos para que a remoção possa ser desfeita.
+    #[pallet::storage]
+    #[pallet::getter(fn last_removed_product)]
+    pub type LastRemovedProduct<T: Config> = StorageValue<_, (ProductOf<T>, Option<T::AccountId>, BlockNumberFor<T>), OptionQuery>;
+
+    /// Produtos arquivados, retirados do catálogo ativo mas mantidos para consulta.
+    #[pallet::storage]
+    #[pallet::getter(fn archived_products)]
+    pub type ArchivedProducts<T: Config> = StorageMap<_, Blake2_128Concat, u64, ProductOf<T>, OptionQuery>;
+
+    /// Posição em que o próximo arquivamento em lote continua, quando o anterior não percorreu todo o catálogo.
+    #[pallet::storage]
+    #[pallet::getter(fn archive_cursor)]
+    pub type ArchiveCursor<T> = StorageValue<_, StorageCursor, OptionQuery>;
+
+    /// Destino e posição em que a próxima transferência do catálogo continua, quando a anterior não percorreu
+    /// todo o catálogo.
+    #[pallet::storage]
+    pub type TransferCursor<T: Config> = StorageValue<_, (T::AccountId, StorageCursor), OptionQuery>;
 
     /// Armazena o próximo ID de produto a ser gerado.
     #[pallet::storage]
@@ -145,20 +798,246 @@ pub mod pallet {
     #[pallet::getter(fn next_sale_code)]
     pub type NextSaleCode<T> = StorageValue<_, u64, ValueQuery>;
 
+    /// Componentes (produtos e quantidades) de cada kit, usando o ID do kit como chave.
+    #[pallet::storage]
+    #[pallet::getter(fn bundles)]
+    pub type Bundles<T: Config> = StorageMap<_, Blake2_128Concat, u64, BoundedVec<ItemSale, T::MaxBundleSize>, OptionQuery>;
+
+    /// Armazena o próximo ID de kit a ser gerado.
+    #[pallet::storage]
+    #[pallet::getter(fn next_bundle_id)]
+    pub type NextBundleId<T> = StorageValue<_, u64, ValueQuery>;
+
+    /// Histórico de vendas de cada produto: quantidade vendida por código de venda.
+    #[pallet::storage]
+    #[pallet::getter(fn product_sales)]
+    pub type ProductSales<T> = StorageDoubleMap<_, Blake2_128Concat, u64, Blake2_128Concat, u64, u64, ValueQuery>;
+
+    /// Quantidade total de unidades vendidas de cada produto.
+    #[pallet::storage]
+    #[pallet::getter(fn units_sold)]
+    pub type UnitsSold<T> = StorageMap<_, Blake2_128Concat, u64, u64, ValueQuery>;
+
+    /// Unidades vendidas de cada produto por bloco, limitadas às amostras dentro de `VelocityWindow`.
+    #[pallet::storage]
+    pub type RecentSales<T: Config> = StorageMap<_, Blake2_128Concat, u64, BoundedVec<(BlockNumberFor<T>, u64), T::MaxVelocitySamples>, ValueQuery>;
+
+    /// Bloco em que cada produto foi adicionado, usado para calcular a velocidade de vendas.
+    #[pallet::storage]
+    #[pallet::getter(fn product_created_at)]
+    pub type ProductCreatedAt<T: Config> = StorageMap<_, Blake2_128Concat, u64, BlockNumberFor<T>, OptionQuery>;
+
+    /// Bloco da venda mais recente de cada produto.
+    #[pallet::storage]
+    #[pallet::getter(fn last_sold_at)]
+    pub type LastSoldAt<T: Config> = StorageMap<_, Blake2_128Concat, u64, BlockNumberFor<T>, OptionQuery>;
+
+    /// Produtos que já tiveram alguma venda, usados para sinalizar a primeira venda de cada produto.
+    #[pallet::storage]
+    pub type FirstSold<T> = StorageMap<_, Blake2_128Concat, u64, (), OptionQuery>;
+
+    /// Remoções de produtos aguardando aprovação, com a conta que as solicitou.
+    #[pallet::storage]
+    #[pallet::getter(fn pending_deletions)]
+    pub type PendingDeletions<T: Config> = StorageMap<_, Blake2_128Concat, u64, T::AccountId, OptionQuery>;
+
+    /// Quantidade de vendas distintas que incluíram cada produto, independente da quantidade vendida.
+    #[pallet::storage]
+    #[pallet::getter(fn sale_tx_count)]
+    pub type SaleTxCount<T> = StorageMap<_, Blake2_128Concat, u64, u32, ValueQuery>;
+
+    /// Déficit acumulado de cada produto vendido além do estoque disponível.
+    #[pallet::storage]
+    #[pallet::getter(fn backorders)]
+    pub type Backorders<T> = StorageMap<_, Blake2_128Concat, u64, u64, ValueQuery>;
+
+    /// Bloco em que cada venda foi criada.
+    #[pallet::storage]
+    #[pallet::getter(fn sale_created_at)]
+    pub type SaleCreatedAt<T: Config> = StorageMap<_, Blake2_128Concat, u64, BlockNumberFor<T>, OptionQuery>;
+
+    /// Fila das vendas em ordem de criação, usada para remover as vendas antigas sem percorrer
+    /// `SaleCreatedAt`. Cada posição guarda o bloco de criação e o código da venda.
+    #[pallet::storage]
+    pub type SalePruneQueue<T: Config> = StorageMap<_, Blake2_128Concat, u64, (BlockNumberFor<T>, u64), OptionQuery>;
+
+    /// Posição da venda mais antiga e próxima posição livre da fila `SalePruneQueue`.
+    #[pallet::storage]
+    pub type SalePruneQueueBounds<T> = StorageValue<_, (u64, u64), ValueQuery>;
+
+    /// Comprador (conta que assinou o registro) de cada venda.
+    #[pallet::storage]
+    #[pallet::getter(fn sale_buyer)]
+    pub type SaleBuyers<T: Config> = StorageMap<_, Blake2_128Concat, u64, T::AccountId, OptionQuery>;
+
+    /// Valor repassado a cada consignante em cada venda, devolvido ao comprador no estorno.
+    #[pallet::storage]
+    pub type SaleConsignments<T: Config> = StorageDoubleMap<_, Blake2_128Concat, u64, Blake2_128Concat, T::AccountId, BalanceOf<T>, OptionQuery>;
+
+    /// Valor total gasto por cada comprador. Mantido em u128, como os demais totais, para não
+    /// estourar ao longo da vida da chain.
+    #[pallet::storage]
+    #[pallet::getter(fn buyer_spend)]
+    pub type BuyerSpend<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u128, ValueQuery>;
+
+    /// Quantidade de vendas registradas no bloco atual, zerada no início de cada bloco.
+    #[pallet::storage]
+    #[pallet::getter(fn sales_this_block)]
+    pub type SalesThisBlock<T> = StorageValue<_, u32, ValueQuery>;
+
+    /// Saldo de cada cartão-presente emitido, usando o identificador do cartão como chave.
+    #[pallet::storage]
+    #[pallet::getter(fn gift_cards)]
+    pub type GiftCards<T> = StorageMap<_, Blake2_128Concat, [u8; 16], u64, OptionQuery>;
+
+    /// Taxa de câmbio de cada moeda, em pontos-base da moeda base:
+    /// uma unidade da moeda vale `taxa / 10_000` unidades da moeda base.
+    #[pallet::storage]
+    #[pallet::getter(fn exchange_rates)]
+    pub type ExchangeRates<T> = StorageMap<_, Blake2_128Concat, [u8; 3], u32, OptionQuery>;
+
+    /// Alíquota de cada categoria tributária, em pontos-base.
+    #[pallet::storage]
+    #[pallet::getter(fn tax_rates)]
+    pub type TaxRates<T> = StorageMap<_, Blake2_128Concat, u8, u32, OptionQuery>;
+
+    /// Receita total das vendas de cada categoria de produto, sem as parcelas dos consignantes.
+    #[pallet::storage]
+    #[pallet::getter(fn category_revenue)]
+    pub type CategoryRevenue<T> = StorageMap<_, Blake2_128Concat, Category, u128, ValueQuery>;
+
+    /// Limite de estoque total de cada categoria de produto. Categorias sem entrada não têm limite.
+    #[pallet::storage]
+    #[pallet::getter(fn category_stock_caps)]
+    pub type CategoryStockCaps<T> = StorageMap<_, Blake2_128Concat, Category, u64, OptionQuery>;
+
+    /// Estoque total dos produtos de cada categoria, mantido para verificar os limites sem percorrer o catálogo.
+    #[pallet::storage]
+    #[pallet::getter(fn category_stock_total)]
+    pub type CategoryStockTotal<T> = StorageMap<_, Blake2_128Concat, Category, u64, ValueQuery>;
+
+    /// Sobretaxa de cada método de pagamento, em pontos-base.
+    /// Métodos sem entrada não têm sobretaxa.
+    #[pallet::storage]
+    #[pallet::getter(fn payment_surcharges)]
+    pub type PaymentSurcharges<T> = StorageMap<_, Blake2_128Concat, PaymentMethod, u32, OptionQuery>;
+
+    /// Soma do valor de todas as vendas registradas e não removidas, sem as parcelas dos consignantes.
+    /// Vendas removidas por antiguidade continuam contabilizadas. Mantida em u128 para não
+    /// estourar ao longo da vida da chain.
+    #[pallet::storage]
+    #[pallet::getter(fn total_sales_value)]
+    pub type TotalSalesValue<T> = StorageValue<_, u128, ValueQuery>;
+
+    /// Quantidade de vendas contabilizadas em `TotalSalesValue`.
+    #[pallet::storage]
+    #[pallet::getter(fn sale_count)]
+    pub type SaleCount<T> = StorageValue<_, u64, ValueQuery>;
+
+    /// Indica se o pallet está pausado para manutenção, bloqueando as chamadas que alteram dados.
+    #[pallet::storage]
+    #[pallet::getter(fn paused)]
+    pub type Paused<T> = StorageValue<_, bool, ValueQuery>;
+
+    /// Indica se os preços dos produtos já incluem o imposto. Nesse caso, o imposto
+    /// é extraído do valor de cada item em vez de calculado sobre ele.
+    #[pallet::storage]
+    #[pallet::getter(fn prices_include_tax)]
+    pub type PricesIncludeTax<T> = StorageValue<_, bool, ValueQuery>;
+
+    /// Produtos travados por operações administrativas, que não podem ser vendidos nem alterados.
+    #[pallet::storage]
+    #[pallet::getter(fn locked_products)]
+    pub type LockedProducts<T> = StorageMap<_, Blake2_128Concat, u64, (), OptionQuery>;
+
+    /// Receita total das vendas registradas em cada data, sem as parcelas dos consignantes.
+    #[pallet::storage]
+    #[pallet::getter(fn daily_revenue)]
+    pub type DailyRevenue<T> = StorageMap<_, Blake2_128Concat, Date, u128, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		ProductAdded(u64),
-		ProductGotten(Product),
-        ProductsToRestock(Vec<Product>),
-		ProductsListed(Vec<Product>),
+		ProductGotten(ProductOf<T>),
+        ProductsToRestock(Vec<ProductOf<T>>),
+		ProductsListed(Vec<ProductOf<T>>),
 		ProductUpdated(u64),
         ProductRemoved(u64),
         SaleRegistered(u64),
 		SaleGotten(Sale),
 		SalesListed(Vec<Sale>),
         SaleUpdated(u64),
-        SaleRemoved(u64)
+        SaleRemoved(u64),
+        DailyRevenueGotten(Date, u128),
+        StockTransferred(u64, u64, u64),
+        ProductsToRestockCount(u32),
+        ProductsListedCount(u32),
+        SalesListedCount(u32),
+        ProductCounterRepaired(u64),
+        SaleCounterRepaired(u64),
+        ProductNeedsRestock(u64),
+        RestockAlertCleared(u64),
+        ProductsBelowStock(Vec<ProductOf<T>>),
+        ProductsBelowStockCount(u32),
+        CategoryReassigned(Category, Category, u32),
+        TopBuyers(Vec<(T::AccountId, u128)>),
+        GiftCardIssued([u8; 16], u64),
+        /// Cartão-presente usado em uma venda: cartão, valor debitado e saldo restante.
+        GiftCardRedeemed([u8; 16], u64, u64),
+        ExchangeRateSet([u8; 3], Option<u32>),
+        BundleCreated(u64),
+        CommissionAccrued(Vec<u8>, u64),
+        ProductSalesListed(u64, Vec<(u64, u64)>),
+        SnapshotImported(u32, u32),
+        Backordered(u64, u64),
+        TaxRateSet(u8, Option<u32>),
+        PausedSet(bool),
+        RestockSlaBreached(u64, BlockNumberFor<T>),
+        CategoryRevenueGotten(Category, u128),
+        AllPricesAdjusted(u32, i64),
+        DeadStockListed(Vec<u64>),
+        SaleFeeCharged(BalanceOf<T>),
+        NewCategoryProduct(Category, u64),
+        BulkArchived(u32),
+        ProductTransferred(u64, T::AccountId, T::AccountId),
+        OwnerProductsListed(T::AccountId, Vec<u64>),
+        PaymentSurchargeSet(PaymentMethod, Option<u32>),
+        SurchargeApplied(PaymentMethod, u64),
+        SalesPruned(u32),
+        ProductCloned(u64, u64),
+        BulkQuantitySold(u64, u64),
+        ProductRestocked(u64, u64),
+        ProductSalesBetween(u64, Vec<u64>),
+        ProductLockSet(u64, bool),
+        ChangeDue(u64, u64),
+        CategoryStockCapSet(Category, Option<u64>),
+        MemberPriceSet(u64, Option<u64>),
+        SaleCodeReset(u64),
+        PromoSet(u64, Option<(Date, Date, u8)>),
+        ProductRestored(u64),
+        ProductsByPrice(Vec<(u64, u64)>),
+        PricesIncludeTaxSet(bool),
+        SaleAmended(u64),
+        SaleCategoryBreakdown(u64, Vec<(Category, u64)>),
+        RecentSales(Vec<(u64, u64)>),
+        CostPriceSet(u64, Option<u64>),
+        CategoryRestockDateSet(Category, u32),
+        ProductFirstSold(u64, BlockNumberFor<T>),
+        DeletionPending(u64),
+        ConsignorSet(u64, Option<T::AccountId>),
+        ConsignmentSettled(T::AccountId, BalanceOf<T>),
+        CartValid(u64),
+        CartItemRejected(u64, DispatchError),
+        /// Item de um carrinho sem estoque suficiente: produto, quantidade pedida e quantidade disponível.
+        SaleValidationFailed(u64, u64, u64),
+        /// Item de um carrinho em que o total estourou: produto e total acumulado dos itens anteriores.
+        SaleComputationOverflow(u64, u64),
+        FlashPriceSet(u64, u64, BlockNumberFor<T>),
+        PriceFloorSet(u64, Option<u64>),
+        PriceFloored(u64),
+        CatalogTransferred(T::AccountId, u32),
+        ConsignmentRefunded(T::AccountId, BalanceOf<T>)
 	}
 
     /// Enumeração de erros que podem ocorrer durante a execução do pallet.
@@ -168,39 +1047,103 @@ pub mod pallet {
         SaleNotFound,        // Venda não encontrada
         InsufficientStock,   // Estoque insuficiente
         InvalidDate,         // Data inválida
-        Overflow             // Overflow durante cálculos
+        Overflow,            // Overflow durante cálculos
+        RestockAmountTooLarge, // Quantidade de reposição acima do limite
+        SameProduct,         // Origem e destino são o mesmo produto
+        InvalidClearLevel,   // Nível de encerramento do alerta abaixo do limite de reposição
+        SameCategory,        // Categorias de origem e destino iguais
+        InvalidDiscount,     // Desconto percentual acima de 100
+        SaleRateLimited,     // Limite de vendas do bloco atingido
+        InvalidGiftCard,     // Cartão-presente inexistente
+        GiftCardAlreadyExists, // Cartão-presente já emitido
+        UnknownCurrency,     // Moeda sem taxa de câmbio cadastrada
+        InvalidExchangeRate, // Taxa de câmbio igual a zero
+        BundleNotFound,      // Kit não encontrado
+        InvalidBundle,       // Kit vazio, grande demais ou com quantidade zero
+        InvalidSnapshot,     // Snapshot grande demais ou com IDs/códigos repetidos
+        SaleCodeCollision,   // Código de venda já utilizado
+        Paused,              // Pallet pausado para manutenção
+        QuantityBelowMinimum, // Quantidade do item abaixo do mínimo do produto
+        QuantityAboveMaximum, // Quantidade do item acima do máximo do produto
+        InvalidOrderLimits,  // Quantidade mínima por venda maior que a máxima
+        InvalidMemberPrice,  // Preço para membros maior que o preço padrão
+        InvalidPromo,        // Promoção com início após o fim ou desconto acima de 100%
+        InsufficientBalanceForFee, // Comprador sem saldo para pagar a taxa da venda
+        InvalidQuantity,     // Quantidade vendida igual a zero
+        RestockDateInPast,   // Data de reposição anterior à data atual
+        NotProductOwner,     // Conta não é a responsável pelo produto
+        ProductLocked,       // Produto travado por uma operação administrativa
+        InsufficientTender,  // Valor entregue em dinheiro menor que o total da venda
+        CategoryCapExceeded, // Estoque total da categoria acima do limite
+        CodeWouldCollide,    // Novo código de venda não é maior que os códigos existentes
+        NothingToUndo,       // Nenhuma remoção de produto a desfazer
+        UndoWindowExpired,   // Prazo para desfazer a remoção encerrado
+        ProductIdReused,     // ID do produto removido já foi reutilizado
+        ProductNotListable,  // Produto com estoque abaixo do mínimo para venda
+        AttributeLimitExceeded, // Atributos demais ou com chave/valor longos demais
+        PaymentMismatch,     // Parcelas do pagamento não somam o total da venda
+        EditWindowExpired,   // Prazo para corrigir a venda encerrado
+        ProductNotInSale,    // Produto não faz parte da venda
+        NothingToUpdate,     // Atualização sem nenhum campo informado
+        MarginTooLow,        // Margem do produto abaixo do mínimo exigido
+        RefundWindowExpired, // Prazo para estornar a venda encerrado
+        DeletionAlreadyPending, // Remoção do produto já aguarda aprovação
+        NoPendingDeletion,   // Nenhuma remoção do produto aguardando aprovação
+        ConsignmentPaymentFailed, // Comprador sem saldo para pagar o consignante
+        FlashPriceExpired,   // Fim do preço relâmpago anterior ao bloco atual
+        FractionalQuantity,  // Fração de unidade em produto vendido por peça
+        InvalidPriceFloor,   // Piso de preço maior que o preço do produto
+        TooManyFlashExpiries, // Limite de preços relâmpago terminando no mesmo bloco atingido
+        TransferInProgress,  // Transferência do catálogo para outra conta ainda em andamento
+        ConsignedSale,       // Venda com itens em consignação não pode ser corrigida
+        ConsignmentRefundFailed // Consignante sem saldo para devolver o repasse do estorno
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            // Reinício do contador de vendas do bloco
+            SalesThisBlock::<T>::kill();
+
+            // Sinalização dos produtos que ultrapassaram o prazo de reposição, uma única vez por alerta
+            let (mut reads, sla_writes) = Self::check_restock_sla(n);
+
+            // Remoção dos preços relâmpago que expiram neste bloco; os substituídos por outra oferta são mantidos
+            let expiring = FlashExpiries::<T>::take(n);
+            reads += 1 + expiring.len() as u64;
+            let mut flash_writes: u64 = 1;
+            for id in expiring {
+                if FlashPrices::<T>::get(id).map_or(false, |(_, until)| until < n) {
+                    FlashPrices::<T>::remove(id);
+                    flash_writes += 1;
+                }
+            }
+
+            // Remoção das vendas mais antigas que o período de retenção
+            let (pruned_reads, pruned_writes) = Self::prune_old_sales(n);
+
+            T::DbWeight::get().reads_writes(reads + pruned_reads, 1 + sla_writes + flash_writes + pruned_writes)
+        }
+
+        fn integrity_test() {
+            // Com incremento zero, cada novo produto ou venda sobrescreveria o anterior
+            assert!(T::IdStep::get() > 0, "IdStep deve ser maior que zero");
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Função para adicionar um novo produto.
         /// O produto é adicionado ao sistema, o ID é gerado automaticamente e o evento `ProductAdded` é disparado.
+        /// Sem `unit`, o estoque é contado em peças.
         #[pallet::call_index(0)]
         #[pallet::weight(10_000)]
-        pub fn add_product( origin: OriginFor<T>, name: Vec<u8>, stock: u64, price: u64, amount_to_restock: u64, restock_date: Date, category: Category) -> DispatchResult {
-            let _who = ensure_signed(origin)?;
-
-            // Validação da data
-            let restock_date = Date::new(restock_date.day, restock_date.month, restock_date.year).map_err(|_| Error::<T>::InvalidDate)?;
-
-            let product_id = Self::next_product_id();
-
-            let product = Product {
-                name,
-                id: product_id,
-                stock,
-                price,
-                amount_to_restock,
-                restock_date,
-                category
-            };
-
-            // Inserção do produto no armazenamento
-            Products::<T>::insert(product_id, product);
-            NextProductId::<T>::put(product_id + 1);
+        pub fn add_product( origin: OriginFor<T>, name: Vec<u8>, stock: u64, price: u64, amount_to_restock: u64, restock_date: Date, category: Category, metadata_hash: Option<[u8; 32]>, restock_clear_level: Option<u64>, min_order_qty: Option<u64>, max_order_qty: Option<u64>, attributes: Vec<(Vec<u8>, Vec<u8>)>, unit: Option<StockUnitLabel>) -> DispatchResult {
+            let who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
 
-            // Emissão do evento
-            Self::deposit_event(Event::ProductAdded(product_id));
+            let attributes = Self::bound_attributes(attributes)?;
+            Self::do_add_product(who, name, stock, price, amount_to_restock, restock_date, category, metadata_hash, restock_clear_level, min_order_qty, max_order_qty, attributes, unit.unwrap_or_default())?;
 
             Ok(())
         }
@@ -226,9 +1169,9 @@ pub mod pallet {
 		pub fn list_products_to_restock(origin: OriginFor<T>) -> DispatchResult {
 			let _who = ensure_signed(origin)?;
 
-			let products: Vec<Product> = Products::<T>::iter()
-            .filter_map(|(_, product)| {
-                if product.stock < product.amount_to_restock {
+			let products: Vec<ProductOf<T>> = Products::<T>::iter()
+            .filter_map(|(id, product)| {
+                if Self::needs_reorder(id, &product) {
                     Some(product)
                 } else {
                     None
@@ -236,7 +1179,11 @@ pub mod pallet {
             })
             .collect();
 
-			Self::deposit_event(Event::ProductsToRestock(products));
+			if T::EmitListingPayloads::get() {
+				Self::deposit_event(Event::ProductsToRestock(products));
+			} else {
+				Self::deposit_event(Event::ProductsToRestockCount(products.len() as u32));
+			}
 
 			Ok(())
 		}
@@ -246,20 +1193,44 @@ pub mod pallet {
 		pub fn list_all_products(origin: OriginFor<T>) -> DispatchResult {
 			let _who = ensure_signed(origin)?;
 
-            // Obtenção de todos os produtos e emissão do evento
-            let products: Vec<Product> = Products::<T>::iter().map(|(_, product)| product).collect();
-            Self::deposit_event(Event::ProductsListed(products));
+            // Obtenção de todos os produtos disponíveis e emissão do evento
+            let products: Vec<ProductOf<T>> = Products::<T>::iter()
+                .map(|(_, product)| product)
+                .filter(Self::is_listable)
+                .collect();
+
+            if T::EmitListingPayloads::get() {
+                Self::deposit_event(Event::ProductsListed(products));
+            } else {
+                Self::deposit_event(Event::ProductsListedCount(products.len() as u32));
+            }
 
             Ok(())
         }
 
+        /// Função para atualizar as propriedades de um produto. O peso declarado cobre o pior caso: alteração
+        /// de preço, estoque, categoria e data de reposição verificada contra o horário on-chain, em categorias
+        /// com limite de estoque, abrindo um alerta de reposição por dias de cobertura. São 20 leituras
+        /// (origem, pausa, produto, trava, histórico de preços, horário, limite e estoque da categoria, índice
+        /// e contagem das duas categorias, estoque das duas categorias, alerta, criação do produto, unidades
+        /// vendidas, fila de SLA, existência do produto e versão do catálogo) e 13 escritas.
+        /// Sem alterações, apenas as leituras feitas são cobradas.
         #[pallet::call_index(4)]
-		#[pallet::weight(10_000)]
-        pub fn update_product(origin: OriginFor<T>, id: u64, name: Option<Vec<u8>>, stock: Option<u64>, price: Option<u64>, amount_to_restock: Option<u64>, restock_date: Option<Date>, category: Option<Category>) -> DispatchResult {
-            let _who = ensure_signed(origin)?;
+		#[pallet::weight(T::DbWeight::get().reads_writes(20, 13).saturating_add(Weight::from_parts(10_000, 0)))]
+        pub fn update_product(origin: OriginFor<T>, id: u64, name: Option<Vec<u8>>, stock: Option<u64>, price: Option<u64>, amount_to_restock: Option<u64>, restock_date: Option<Date>, category: Option<Category>, metadata_hash: Option<Option<[u8; 32]>>, restock_clear_level: Option<Option<u64>>, attributes: Option<Vec<(Vec<u8>, Vec<u8>)>>, unit: Option<StockUnitLabel>) -> DispatchResultWithPostInfo {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            if T::RejectEmptyUpdates::get() {
+                let any_field = name.is_some() || stock.is_some() || price.is_some() || amount_to_restock.is_some() || restock_date.is_some()
+                    || category.is_some() || metadata_hash.is_some() || restock_clear_level.is_some() || attributes.is_some() || unit.is_some();
+                ensure!(any_field, Error::<T>::NothingToUpdate);
+            }
 
             // Obtenção do produto a ser atualizado
             let mut product = Products::<T>::get(id).ok_or(Error::<T>::ProductNotFound)?;
+            Self::ensure_not_locked(id)?;
+            let original = product.clone();
 
             // Atualização das propriedades com base nos parâmetros fornecidos
             if let Some(new_name) = name {
@@ -270,80 +1241,179 @@ pub mod pallet {
                 product.stock = new_stock;
             }
 
+            if let Some(new_unit) = unit {
+                product.unit = new_unit;
+            }
+            ensure!(product.accepts_quantity(product.stock, T::StockUnit::get()), Error::<T>::FractionalQuantity);
+
             if let Some(new_price) = price {
+                if new_price != product.price {
+                    Self::record_price(id, new_price);
+                }
                 product.price = new_price;
             }
 
             if let Some(new_amount_to_restock) = amount_to_restock {
+                ensure!(new_amount_to_restock <= T::MaxRestockAmount::get(), Error::<T>::RestockAmountTooLarge);
                 product.amount_to_restock = new_amount_to_restock;
             }
 
+            // `Some(None)` volta a usar o limite de reposição como nível de encerramento
+            if let Some(new_clear_level) = restock_clear_level {
+                product.restock_clear_level = new_clear_level;
+            }
+
+            ensure!(product.has_valid_clear_level(), Error::<T>::InvalidClearLevel);
+            ensure!(product.has_valid_member_price(), Error::<T>::InvalidMemberPrice);
+            ensure!(product.has_valid_price_floor(), Error::<T>::InvalidPriceFloor);
+            ensure!(product.has_min_margin(T::MinMarginBasisPoints::get()), Error::<T>::MarginTooLow);
+
             if let Some(new_restock_date) = restock_date {
-                let new_date = Date::new(new_restock_date.day, new_restock_date.month, new_restock_date.year).map_err(|_| Error::<T>::InvalidDate)?;
+                let new_date = Self::validate_date(new_restock_date)?;
+                Self::ensure_restock_date_not_past(&new_date)?;
                 product.restock_date = new_date;
             }
 
+            // Verificação do limite de estoque da categoria de destino
+            let target_category = category.clone().unwrap_or_else(|| product.category.clone());
+            let added = if target_category == original.category { product.stock.saturating_sub(original.stock) } else { product.stock };
+            Self::ensure_category_capacity(&target_category, added)?;
+
             if let Some(new_category) = category {
+                // Atualização do índice de categorias
+                if new_category != product.category {
+                    Self::unindex_category(&product.category, id);
+                    Self::index_category(&new_category, id);
+                }
                 product.category = new_category;
             }
 
+            // `Some(None)` remove o hash de metadados do produto
+            if let Some(new_metadata_hash) = metadata_hash {
+                product.metadata_hash = new_metadata_hash;
+            }
+
+            // Os atributos informados substituem todos os atributos atuais
+            if let Some(new_attributes) = attributes {
+                product.attributes = Self::bound_attributes(new_attributes)?;
+            }
+
+            // Nenhum campo alterado: apenas as leituras da origem, da pausa, do produto, da trava, do horário e do
+            // limite e estoque da categoria são cobradas
+            if product == original {
+                return Ok(Some(T::DbWeight::get().reads(7).saturating_add(Weight::from_parts(10_000, 0))).into());
+            }
+
             // Salvar produto atualizado
+            Self::decrease_category_stock(&original.category, original.stock);
+            Self::increase_category_stock(&product.category, product.stock);
+            Self::update_restock_alert(id, &product);
             Products::<T>::insert(id, product);
+            Self::bump_catalog_version();
             Self::deposit_event(Event::ProductUpdated(id));
 
-            Ok(())
+            Ok(().into())
         }
 
         #[pallet::call_index(5)]
 		#[pallet::weight(10_000)]
         pub fn remove_product(origin: OriginFor<T>, id: u64) -> DispatchResult {
-            let _who = ensure_signed(origin)?;
-
-            ensure!(Products::<T>::contains_key(id), Error::<T>::ProductNotFound);
+            let who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            let product = Products::<T>::get(id).ok_or(Error::<T>::ProductNotFound)?;
+            Self::ensure_not_locked(id)?;
+
+            // Produtos de alto valor em estoque aguardam a aprovação de um administrador; o estoque está na
+            // escala de `StockUnit`, e um valor que estoura u64 certamente supera o limite
+            let threshold = T::DeletionApprovalThreshold::get();
+            let stock_value = Self::line_value(product.price, product.stock, None);
+            if threshold > 0 && stock_value.map_or(true, |value| value > threshold) {
+                ensure!(!PendingDeletions::<T>::contains_key(id), Error::<T>::DeletionAlreadyPending);
+                PendingDeletions::<T>::insert(id, who);
+                Self::deposit_event(Event::DeletionPending(id));
+                return Ok(());
+            }
 
-            // Remover o produto
-            Products::<T>::remove(id);
-            Self::deposit_event(Event::ProductRemoved(id));
+            Self::do_remove_product(id, product);
 
             Ok(())
         }
 
-        #[pallet::call_index(6)]
-		#[pallet::weight(10_000)]
-        pub fn register_sale(origin: OriginFor<T>, seller: Vec<u8>, products: Vec<ItemSale>, payment_method: PaymentMethod) -> DispatchResult {
-            let _who = ensure_signed(origin)?;
+        /// Função administrativa para aprovar uma remoção de produto pendente, removendo o produto.
+        #[pallet::call_index(54)]
+        #[pallet::weight(10_000)]
+        pub fn approve_deletion(origin: OriginFor<T>, id: u64) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
 
-            let mut total_value: u64 = 0;
-            let mut sale_products: Vec<u64> = Vec::new();
+            ensure!(PendingDeletions::<T>::contains_key(id), Error::<T>::NoPendingDeletion);
+            let product = Products::<T>::get(id).ok_or(Error::<T>::ProductNotFound)?;
+            Self::ensure_not_locked(id)?;
 
-            // Processamento de cada item da venda
-            for item in products {
-                let mut product = Products::<T>::get(item.product_id).ok_or(Error::<T>::ProductNotFound)?;
-                product.stock = product.stock.checked_sub(item.amount).ok_or(Error::<T>::InsufficientStock)?;
-                Products::<T>::insert(item.product_id, &product);
+            Self::do_remove_product(id, product);
 
-                if !sale_products.contains(&item.product_id) {
-                    sale_products.push(item.product_id);
-                }
+            Ok(())
+        }
 
-                let partial_value = product.price.checked_mul(item.amount).ok_or(Error::<T>::Overflow)?;
-                total_value = total_value.checked_add(partial_value).ok_or(Error::<T>::Overflow)?;
-            }
+        /// Função para desfazer a remoção de produto mais recente, dentro de `UndoWindow` blocos.
+        /// O produto restaurado volta ao seu dono original, ou passa a pertencer a quem desfez a remoção se não
+        /// tinha dono; o histórico de vendas não é recuperado.
+        #[pallet::call_index(46)]
+        #[pallet::weight(10_000)]
+        pub fn undo_last_removal(origin: OriginFor<T>) -> DispatchResult {
+            let who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            let (product, owner, removed_at) = LastRemovedProduct::<T>::get().ok_or(Error::<T>::NothingToUndo)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(now.saturating_sub(removed_at) <= T::UndoWindow::get(), Error::<T>::UndoWindowExpired);
+
+            let id = product.id;
+            ensure!(!Products::<T>::contains_key(id), Error::<T>::ProductIdReused);
+            Self::ensure_category_capacity(&product.category, product.stock)?;
+
+            // Restauração do produto e dos índices
+            LastRemovedProduct::<T>::kill();
+            FreeProductIds::<T>::mutate(|free_ids| free_ids.retain(|free_id| *free_id != id));
+            Self::index_category(&product.category, id);
+            Self::increase_category_stock(&product.category, product.stock);
+            Self::record_price(id, product.price);
+            ProductCreatedAt::<T>::insert(id, now);
+            let owner = owner.unwrap_or(who);
+            OwnerProducts::<T>::insert(&owner, id, ());
+            ProductOwners::<T>::insert(id, owner);
+            Self::update_restock_alert(id, &product);
+            Products::<T>::insert(id, product);
 
-            let sale_code = Self::next_sale_code();
-            let sale = Sale {
-                seller,
-                code: sale_code,
-                products: sale_products,
-                value: total_value,
-                date: Date::new(3, 2, 2025).unwrap(),
-                payment_method
-            };
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::ProductRestored(id));
 
-            // Inserir venda no armazenamento
-            Sales::<T>::insert(sale_code, sale);
-            NextSaleCode::<T>::put(sale_code + 1);
-            Self::deposit_event(Event::SaleRegistered(sale_code));
+            Ok(())
+        }
+
+        #[pallet::call_index(6)]
+		#[pallet::weight(10_000)]
+        pub fn register_sale(origin: OriginFor<T>, seller: Vec<u8>, products: Vec<ItemSale>, bundles: Vec<u64>, payment_method: PaymentMethod, date: Date, gift_card: Option<[u8; 16]>, currency: Option<[u8; 3]>, is_member: bool) -> DispatchResult {
+            let who = T::CustomerOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            Self::do_register_sale(who, seller, products, bundles, payment_method, None, date, gift_card, currency, is_member)?;
+
+            Ok(())
+        }
+
+        /// Função para registrar uma venda paga com mais de um método de pagamento.
+        /// As parcelas `(método, valor)` devem somar exatamente o total da venda, descontadas a parte paga
+        /// com o cartão-presente e a repassada aos consignantes; a sobretaxa aplicada é a do método da primeira parcela.
+        #[pallet::call_index(48)]
+        #[pallet::weight(10_000)]
+        pub fn register_split_sale(origin: OriginFor<T>, seller: Vec<u8>, products: Vec<ItemSale>, bundles: Vec<u64>, payments: Vec<(PaymentMethod, u64)>, date: Date, gift_card: Option<[u8; 16]>, currency: Option<[u8; 3]>, is_member: bool) -> DispatchResult {
+            let who = T::CustomerOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            let payment_method = payments.first().map(|(method, _)| method.clone()).ok_or(Error::<T>::PaymentMismatch)?;
+            Self::do_register_sale(who, seller, products, bundles, payment_method, Some(payments), date, gift_card, currency, is_member)?;
 
             Ok(())
         }
@@ -368,51 +1438,2490 @@ pub mod pallet {
 
             // Obtenção de todas as vendas e emissão do evento
             let sales: Vec<Sale> = Sales::<T>::iter().map(|(_, sale)| sale).collect();
-            Self::deposit_event(Event::SalesListed(sales));
+
+            if T::EmitListingPayloads::get() {
+                Self::deposit_event(Event::SalesListed(sales));
+            } else {
+                Self::deposit_event(Event::SalesListedCount(sales.len() as u32));
+            }
 
             Ok(())
         }
 
+        /// Função para atualizar o vendedor, a data ou o método de pagamento de uma venda. O peso declarado
+        /// cobre a mudança de data, que move o valor entre as receitas diárias: 7 leituras (origem, pausa,
+        /// venda, as duas receitas diárias, existência da venda e versão das vendas) e 4 escritas.
+        /// Sem alterações, apenas as leituras feitas são cobradas.
         #[pallet::call_index(9)]
-		#[pallet::weight(10_000)]
-        pub fn update_sale(origin: OriginFor<T>, code: u64, seller: Option<Vec<u8>>, date: Option<Date>, payment_method: Option<PaymentMethod>) -> DispatchResult {
-            let _who = ensure_signed(origin)?;
+		#[pallet::weight(T::DbWeight::get().reads_writes(7, 4).saturating_add(Weight::from_parts(10_000, 0)))]
+        pub fn update_sale(origin: OriginFor<T>, code: u64, seller: Option<Vec<u8>>, date: Option<Date>, payment_method: Option<PaymentMethod>) -> DispatchResultWithPostInfo {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            if T::RejectEmptyUpdates::get() {
+                ensure!(seller.is_some() || date.is_some() || payment_method.is_some(), Error::<T>::NothingToUpdate);
+            }
 
             // Obtenção da venda existente
             let mut sale = Sales::<T>::get(code).ok_or(Error::<T>::SaleNotFound)?;
+            let original = sale.clone();
 
             if let Some(new_seller) = seller {
                 sale.seller = new_seller;
             }
 
             if let Some(new_date) = date {
-				let new_date = Date::new(new_date.day, new_date.month, new_date.year).map_err(|_| Error::<T>::InvalidDate)?;
+				let new_date = Self::validate_date(new_date)?;
+
+                // Transferência do valor da venda para a receita da nova data
+                if new_date != sale.date {
+                    let store_value = sale.store_value() as u128;
+                    DailyRevenue::<T>::mutate(&sale.date, |revenue| *revenue = revenue.saturating_sub(store_value));
+                    DailyRevenue::<T>::try_mutate(&new_date, |revenue| -> DispatchResult {
+                        *revenue = revenue.checked_add(store_value).ok_or(Error::<T>::Overflow)?;
+                        Ok(())
+                    })?;
+                }
+
                 sale.date = new_date;
             }
 
             if let Some(new_payment_method) = payment_method {
+                // Pagamentos com um único método acompanham a alteração
+                if let [(method, _)] = sale.payments.as_mut_slice() {
+                    *method = new_payment_method.clone();
+                }
                 sale.payment_method = new_payment_method;
             }
 
+            // Nenhum campo alterado: apenas as leituras da origem, da pausa e da venda são cobradas
+            if sale == original {
+                return Ok(Some(T::DbWeight::get().reads(3).saturating_add(Weight::from_parts(10_000, 0))).into());
+            }
+
             // Atualizar venda
             Sales::<T>::insert(code, sale);
+            Self::bump_sales_version();
             Self::deposit_event(Event::SaleUpdated(code));
 
+            Ok(().into())
+        }
+
+        /// Função para corrigir as quantidades dos itens de uma venda, dentro de `SaleEditWindow` blocos.
+        /// Os itens informados substituem os da venda e só podem conter produtos já vendidos nela.
+        /// O estoque é conciliado pela diferença de quantidades e o valor é recalculado aos preços,
+        /// descontos e pisos registrados na venda. A parcela paga com cartão-presente é mantida.
+        #[pallet::call_index(50)]
+        #[pallet::weight(10_000)]
+        pub fn amend_sale_items(origin: OriginFor<T>, code: u64, new_items: Vec<ItemSale>) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            // Vendas estornadas são removidas, então apenas vendas ativas são encontradas
+            let mut sale = Sales::<T>::get(code).ok_or(Error::<T>::SaleNotFound)?;
+            let created_at = SaleCreatedAt::<T>::get(code).ok_or(Error::<T>::EditWindowExpired)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(now.saturating_sub(created_at) <= T::SaleEditWindow::get(), Error::<T>::EditWindowExpired);
+            ensure!(!new_items.is_empty(), Error::<T>::InvalidQuantity);
+            // Pagamentos divididos não podem ser redistribuídos automaticamente
+            ensure!(sale.payments.len() == 1, Error::<T>::PaymentMismatch);
+            // O repasse aos consignantes, já pago na venda, não é refeito
+            ensure!(sale.lines.iter().all(|line| line.consigned == 0 && !Consignors::<T>::contains_key(line.product_id)), Error::<T>::ConsignedSale);
+
+            // Quantidades anterior e nova de cada produto da venda
+            let mut quantities: Vec<(u64, u64, u64)> = sale.products.iter().map(|id| {
+                let previous = sale.lines.iter()
+                    .filter(|line| line.product_id == *id)
+                    .fold(0u64, |total, line| total.saturating_add(line.amount));
+                (*id, previous, 0)
+            }).collect();
+            for item in new_items.iter() {
+                ensure!(item.amount > 0, Error::<T>::InvalidQuantity);
+                let entry = quantities.iter_mut().find(|(id, _, _)| *id == item.product_id).ok_or(Error::<T>::ProductNotInSale)?;
+                entry.2 = entry.2.checked_add(item.amount).ok_or(Error::<T>::Overflow)?;
+            }
+
+            // Conciliação do estoque, validada antes de qualquer escrita
+            let mut updated: Vec<(u64, ProductOf<T>)> = Vec::new();
+            let mut returned: Vec<(Category, u64)> = Vec::new();
+            for (id, previous, amount) in quantities.iter() {
+                if previous == amount {
+                    continue;
+                }
+                let mut product = Products::<T>::get(id).ok_or(Error::<T>::ProductNotFound)?;
+                Self::ensure_not_locked(*id)?;
+                product.stock = if amount > previous {
+                    product.stock.checked_sub(amount - previous).ok_or(Error::<T>::InsufficientStock)?
+                } else {
+                    match returned.iter_mut().find(|(category, _)| *category == product.category) {
+                        Some((_, units)) => *units = units.saturating_add(previous - amount),
+                        None => returned.push((product.category.clone(), previous - amount))
+                    }
+                    product.stock.checked_add(previous - amount).ok_or(Error::<T>::Overflow)?
+                };
+                updated.push((*id, product));
+            }
+            // O estoque devolvido respeita o limite de cada categoria
+            for (category, units) in returned.iter() {
+                Self::ensure_category_capacity(category, *units)?;
+            }
+
+            // Recálculo dos itens, do imposto e do valor da venda
+            let prices_include_tax = Self::prices_include_tax();
+            let mut items_value: u64 = 0;
+            let mut total_tax: u64 = 0;
+            let mut scaled_tax: u128 = 0;
+            let mut lines: Vec<SaleLine> = Vec::new();
+            for item in new_items {
+                let product = match updated.iter().find(|(id, _)| *id == item.product_id) {
+                    Some((_, product)) => product.clone(),
+                    None => Products::<T>::get(item.product_id).ok_or(Error::<T>::ProductNotFound)?
+                };
+                // Preço, desconto e piso do primeiro item do produto na venda, não os atuais
+                let sold = sale.lines.iter().find(|line| line.product_id == item.product_id).ok_or(Error::<T>::ProductNotInSale)?;
+                let discount_percent = item.discount_percent.max(sold.discount_percent);
+                let (value, _) = Self::floored_line_value(sold.unit_price, item.amount, discount_percent, sold.price_floor)?;
+                items_value = items_value.checked_add(value).ok_or(Error::<T>::Overflow)?;
+                let tax = Self::line_tax(value, product.tax_category, prices_include_tax)?;
+                total_tax = total_tax.checked_add(tax).ok_or(Error::<T>::Overflow)?;
+                scaled_tax = scaled_tax.saturating_add(Self::scaled_line_tax(value, product.tax_category, prices_include_tax));
+                lines.push(SaleLine {
+                    product_id: item.product_id,
+                    amount: item.amount,
+                    unit_price: sold.unit_price,
+                    discount_percent,
+                    price_floor: sold.price_floor,
+                    value,
+                    category: sold.category.clone(),
+                    tax,
+                    consigned: 0
+                });
+            }
+            if T::TaxOnSaleTotal::get() {
+                total_tax = Self::allocate_sale_tax(&mut lines, scaled_tax);
+            }
+            let commission = items_value as u128 * T::CommissionBasisPoints::get() as u128 / 10_000;
+            let commission = u64::try_from(commission).map_err(|_| Error::<T>::Overflow)?;
+            let net = if prices_include_tax { items_value.saturating_sub(total_tax) } else { items_value };
+            let surcharge = Self::payment_surcharge(&sale.payment_method, items_value)?;
+            let total_value = items_value.checked_add(surcharge).ok_or(Error::<T>::Overflow)?;
+            // O valor já debitado do cartão-presente não é devolvido, então o novo total precisa cobri-lo
+            let gift_card_redeemed = sale.gift_card.map_or(0, |(_, redeemed)| redeemed);
+            let due = total_value.checked_sub(gift_card_redeemed).ok_or(Error::<T>::PaymentMismatch)?;
+            let display_currency = match sale.display_currency {
+                Some((currency, _)) => {
+                    let rate = ExchangeRates::<T>::get(currency).ok_or(Error::<T>::UnknownCurrency)?;
+                    let converted = T::Rounding::get().divide(total_value as u128 * 10_000, rate as u128);
+                    Some((currency, u64::try_from(converted).map_err(|_| Error::<T>::Overflow)?))
+                }
+                None => None
+            };
+
+            // Novos totais da receita do dia e do comprador
+            let revenue = DailyRevenue::<T>::get(&sale.date).saturating_sub(sale.value as u128).checked_add(total_value as u128).ok_or(Error::<T>::Overflow)?;
+            let total_sales_value = Self::total_sales_value().saturating_sub(sale.value as u128).checked_add(total_value as u128).ok_or(Error::<T>::Overflow)?;
+            let buyer = SaleBuyers::<T>::get(code);
+            let spend = match &buyer {
+                Some(buyer) => BuyerSpend::<T>::get(buyer).saturating_sub(sale.value as u128).checked_add(total_value as u128).ok_or(Error::<T>::Overflow)?,
+                None => 0
+            };
+
+            // Atualização do estoque dos produtos
+            for (id, product) in updated.iter() {
+                let (_, previous, amount) = quantities.iter().find(|(product_id, _, _)| product_id == id).copied().unwrap_or_default();
+                if amount > previous {
+                    Self::decrease_category_stock(&product.category, amount - previous);
+                } else {
+                    Self::increase_category_stock(&product.category, previous - amount);
+                }
+                Self::update_restock_alert(*id, product);
+                Products::<T>::insert(id, product);
+            }
+
+            // Atualização do histórico dos produtos e da receita das categorias
+            for line in sale.lines.iter() {
+                CategoryRevenue::<T>::mutate(&line.category, |revenue| *revenue = revenue.saturating_sub(line.value as u128));
+            }
+            for line in lines.iter() {
+                CategoryRevenue::<T>::mutate(&line.category, |revenue| *revenue = revenue.saturating_add(line.value as u128));
+            }
+            for (id, previous, amount) in quantities.iter() {
+                UnitsSold::<T>::mutate(id, |units| *units = units.saturating_sub(*previous).saturating_add(*amount));
+                if *amount == 0 {
+                    ProductSales::<T>::remove(id, code);
+                    SaleTxCount::<T>::mutate(id, |count| *count = count.saturating_sub(1));
+                } else {
+                    ProductSales::<T>::insert(id, code, amount);
+                }
+            }
+
+            DailyRevenue::<T>::insert(&sale.date, revenue);
+            if let Some(buyer) = buyer {
+                BuyerSpend::<T>::insert(&buyer, spend);
+            }
+            TotalSalesValue::<T>::put(total_sales_value);
+
+            // Atualizar venda
+            sale.products = quantities.iter().filter(|(_, _, amount)| *amount > 0).map(|(id, _, _)| *id).collect();
+            sale.lines = lines;
+            sale.value = total_value;
+            sale.commission = commission;
+            sale.tax = total_tax;
+            sale.net = net;
+            sale.surcharge = surcharge;
+            sale.display_currency = display_currency;
+            sale.payments = vec![(sale.payment_method.clone(), due)];
+            Sales::<T>::insert(code, sale);
+
+            Self::bump_catalog_version();
+            Self::bump_sales_version();
+            Self::deposit_event(Event::SaleAmended(code));
+
             Ok(())
         }
 
+        /// Função para estornar uma venda, dentro de `RefundWindowBlocks` blocos após o seu registro.
+        /// Vendas sem bloco de criação conhecido, como as importadas, podem ser estornadas a qualquer momento.
         #[pallet::call_index(10)]
 		#[pallet::weight(10_000)]
         pub fn remove_sale(origin: OriginFor<T>, code: u64) -> DispatchResult {
-            let _who = ensure_signed(origin)?;
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            let sale = Sales::<T>::get(code).ok_or(Error::<T>::SaleNotFound)?;
+
+            // Validação do prazo de estorno
+            let window = T::RefundWindowBlocks::get();
+            if let Some(created_at) = SaleCreatedAt::<T>::get(code).filter(|_| !window.is_zero()) {
+                let now = frame_system::Pallet::<T>::block_number();
+                ensure!(now.saturating_sub(created_at) <= window, Error::<T>::RefundWindowExpired);
+            }
+
+            // Devolução, pelos consignantes, do que receberam na venda, antes de qualquer outra escrita
+            if let Some(buyer) = SaleBuyers::<T>::get(code) {
+                with_storage_layer(|| -> DispatchResult {
+                    for (consignor, amount) in SaleConsignments::<T>::iter_prefix(code) {
+                        let credit = <T::Currency as fungible::Balanced<T::AccountId>>::withdraw(&consignor, amount, Precision::Exact, Preservation::Expendable, Fortitude::Polite)
+                            .map_err(|_| Error::<T>::ConsignmentRefundFailed)?;
+                        <T::Currency as fungible::Balanced<T::AccountId>>::resolve(&buyer, credit)
+                            .map_err(|_| Error::<T>::ConsignmentRefundFailed)?;
+                        Self::deposit_event(Event::ConsignmentRefunded(consignor, amount));
+                    }
+                    Ok(())
+                })?;
+            }
+            let _ = SaleConsignments::<T>::clear_prefix(code, u32::MAX, None);
+
+            // Estorno do valor na receita do dia e no valor médio das vendas
+            DailyRevenue::<T>::mutate(&sale.date, |revenue| *revenue = revenue.saturating_sub(sale.store_value() as u128));
+            Self::untrack_sale_value(sale.store_value());
 
-            ensure!(Sales::<T>::contains_key(code), Error::<T>::SaleNotFound);
+            // Estorno do valor gasto pelo comprador
+            if let Some(buyer) = SaleBuyers::<T>::take(code) {
+                BuyerSpend::<T>::mutate(&buyer, |spend| *spend = spend.saturating_sub(sale.value as u128));
+            }
+
+            // Devolução do valor debitado do cartão-presente
+            if let Some((card, redeemed)) = sale.gift_card {
+                GiftCards::<T>::mutate(card, |balance| {
+                    if let Some(balance) = balance {
+                        *balance = balance.saturating_add(redeemed);
+                    }
+                });
+            }
+
+            // Remoção da venda do histórico dos produtos e estorno da receita das categorias
+            for line in sale.lines.iter() {
+                ProductSales::<T>::remove(line.product_id, code);
+                CategoryRevenue::<T>::mutate(&line.category, |revenue| *revenue = revenue.saturating_sub(line.store_value() as u128));
+                UnitsSold::<T>::mutate(line.product_id, |units| *units = units.saturating_sub(line.amount));
+            }
+            for id in sale.products.iter() {
+                SaleTxCount::<T>::mutate(id, |count| *count = count.saturating_sub(1));
+            }
 
             // Remover venda
+            SaleCreatedAt::<T>::remove(code);
             Sales::<T>::remove(code);
+            Self::bump_sales_version();
             Self::deposit_event(Event::SaleRemoved(code));
 
             Ok(())
         }
+
+        /// Função para obter a receita total das vendas registradas em uma data.
+        #[pallet::call_index(11)]
+        #[pallet::weight(10_000)]
+        pub fn get_daily_revenue(origin: OriginFor<T>, date: Date) -> DispatchResult {
+            let _who = ensure_signed(origin)?;
+
+            let date = Self::validate_date(date)?;
+            let revenue = DailyRevenue::<T>::get(&date);
+            Self::deposit_event(Event::DailyRevenueGotten(date, revenue));
+
+            Ok(())
+        }
+
+        /// Função administrativa para emitir um cartão-presente com o saldo informado.
+        #[pallet::call_index(18)]
+        #[pallet::weight(10_000)]
+        pub fn issue_gift_card(origin: OriginFor<T>, card: [u8; 16], balance: u64) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            ensure!(!GiftCards::<T>::contains_key(card), Error::<T>::GiftCardAlreadyExists);
+
+            GiftCards::<T>::insert(card, balance);
+            Self::deposit_event(Event::GiftCardIssued(card, balance));
+
+            Ok(())
+        }
+
+        /// Função administrativa para definir ou remover (`None`) a taxa de câmbio de uma moeda.
+        #[pallet::call_index(19)]
+        #[pallet::weight(10_000)]
+        pub fn set_exchange_rate(origin: OriginFor<T>, currency: [u8; 3], rate: Option<u32>) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            match rate {
+                Some(rate) => {
+                    ensure!(rate > 0, Error::<T>::InvalidExchangeRate);
+                    ExchangeRates::<T>::insert(currency, rate);
+                }
+                None => ExchangeRates::<T>::remove(currency)
+            }
+
+            Self::deposit_event(Event::ExchangeRateSet(currency, rate));
+
+            Ok(())
+        }
+
+        /// Função para criar um kit, composto por produtos vendidos em conjunto.
+        #[pallet::call_index(20)]
+        #[pallet::weight(10_000)]
+        pub fn create_bundle(origin: OriginFor<T>, components: Vec<ItemSale>) -> DispatchResult {
+            T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(!components.is_empty(), Error::<T>::InvalidBundle);
+            for component in components.iter() {
+                ensure!(component.amount > 0, Error::<T>::InvalidBundle);
+                ensure!(Products::<T>::contains_key(component.product_id), Error::<T>::ProductNotFound);
+            }
+            let components: BoundedVec<ItemSale, T::MaxBundleSize> = components.try_into().map_err(|_| Error::<T>::InvalidBundle)?;
+
+            let bundle_id = Self::next_bundle_id();
+            Bundles::<T>::insert(bundle_id, components);
+            NextBundleId::<T>::put(bundle_id + 1);
+
+            Self::deposit_event(Event::BundleCreated(bundle_id));
+
+            Ok(())
+        }
+
+        /// Função para listar as vendas que incluíram um produto, com a quantidade vendida em cada uma.
+        #[pallet::call_index(21)]
+        #[pallet::weight(10_000)]
+        pub fn get_product_sales(origin: OriginFor<T>, id: u64, limit: u32) -> DispatchResult {
+            let _who = ensure_signed(origin)?;
+
+            ensure!(Products::<T>::contains_key(id), Error::<T>::ProductNotFound);
+
+            let mut sales: Vec<(u64, u64)> = ProductSales::<T>::iter_prefix(id).collect();
+            sales.sort_by_key(|(code, _)| *code);
+            sales.truncate(Self::page_size(limit));
+
+            Self::deposit_event(Event::ProductSalesListed(id, sales));
+
+            Ok(())
+        }
+
+        /// Função para listar, em ordem crescente, os códigos das vendas que incluíram um produto
+        /// com data entre `from` e `to` (inclusive).
+        #[pallet::call_index(38)]
+        #[pallet::weight(10_000)]
+        pub fn product_sales_between(origin: OriginFor<T>, id: u64, from: Date, to: Date, limit: u32) -> DispatchResult {
+            let _who = ensure_signed(origin)?;
+
+            let from = Self::validate_date(from)?;
+            let to = Self::validate_date(to)?;
+            ensure!(Products::<T>::contains_key(id), Error::<T>::ProductNotFound);
+
+            let mut codes: Vec<u64> = ProductSales::<T>::iter_key_prefix(id)
+                .filter(|code| Sales::<T>::get(code).map_or(false, |sale| sale.date >= from && sale.date <= to))
+                .collect();
+            codes.sort();
+            codes.truncate(Self::page_size(limit));
+
+            Self::deposit_event(Event::ProductSalesBetween(id, codes));
+
+            Ok(())
+        }
+
+        /// Função para adicionar um produto e registrar uma venda inicial dele no mesmo extrínseco.
+        /// Caso a venda falhe, a criação do produto é desfeita.
+        #[pallet::call_index(22)]
+        #[pallet::weight(10_000)]
+        pub fn add_product_and_sell(origin: OriginFor<T>, name: Vec<u8>, stock: u64, price: u64, amount_to_restock: u64, restock_date: Date, category: Category, metadata_hash: Option<[u8; 32]>, restock_clear_level: Option<u64>, min_order_qty: Option<u64>, max_order_qty: Option<u64>, attributes: Vec<(Vec<u8>, Vec<u8>)>, initial_amount: u64, seller: Vec<u8>, payment_method: PaymentMethod, date: Date) -> DispatchResult {
+            let who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            let attributes = Self::bound_attributes(attributes)?;
+            with_storage_layer(|| {
+                let product_id = Self::do_add_product(who.clone(), name, stock, price, amount_to_restock, restock_date, category, metadata_hash, restock_clear_level, min_order_qty, max_order_qty, attributes, StockUnitLabel::Piece)?;
+                let item = ItemSale { product_id, amount: initial_amount, discount_percent: None };
+                Self::do_register_sale(who, seller, vec![item], Vec::new(), payment_method, None, date, None, None, false)?;
+                Ok(())
+            })
+        }
+
+        /// Função para registrar uma venda paga em dinheiro, calculando o troco a partir do valor entregue.
+        /// Caso o valor entregue não cubra o total, a venda é desfeita.
+        #[pallet::call_index(41)]
+        #[pallet::weight(10_000)]
+        pub fn register_cash_sale(origin: OriginFor<T>, seller: Vec<u8>, products: Vec<ItemSale>, bundles: Vec<u64>, date: Date, tendered: u64) -> DispatchResult {
+            let who = T::CustomerOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            with_storage_layer(|| {
+                let sale_code = Self::do_register_sale(who, seller, products, bundles, PaymentMethod::Money, None, date, None, None, false)?;
+                // Os repasses aos consignantes não passam pelo caixa
+                let total = Sales::<T>::get(sale_code).map_or(0, |sale| sale.payments.iter().fold(0u64, |total, (_, amount)| total.saturating_add(*amount)));
+
+                let change = tendered.checked_sub(total).ok_or(Error::<T>::InsufficientTender)?;
+                Self::deposit_event(Event::ChangeDue(sale_code, change));
+
+                Ok(())
+            })
+        }
+
+        /// Função administrativa para restaurar produtos e vendas exportados por `export_snapshot`.
+        /// Os produtos importados passam a pertencer a `owner`, e as vendas só podem citar produtos
+        /// existentes ou importados junto com elas.
+        /// Todos os registros são validados antes da inserção; se algum for inválido, nada é alterado.
+        /// As vendas importadas entram nos mesmos índices das vendas registradas e podem ser estornadas.
+        #[pallet::call_index(23)]
+        #[pallet::weight(10_000)]
+        pub fn import_snapshot(origin: OriginFor<T>, owner: T::AccountId, products: Vec<ProductOf<T>>, sales: Vec<Sale>) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            let max = T::MaxSnapshotSize::get() as usize;
+            ensure!(products.len() <= max && sales.len() <= max, Error::<T>::InvalidSnapshot);
+
+            // Validação dos produtos, com as mesmas regras de `add_product`
+            let mut product_ids: Vec<u64> = Vec::new();
+            let mut category_stock: Vec<(Category, u64)> = Vec::new();
+            for product in products.iter() {
+                Self::validate_date(product.restock_date.clone())?;
+                ensure!(product.amount_to_restock <= T::MaxRestockAmount::get(), Error::<T>::RestockAmountTooLarge);
+                ensure!(product.accepts_quantity(product.stock, T::StockUnit::get()), Error::<T>::FractionalQuantity);
+                ensure!(product.has_valid_clear_level(), Error::<T>::InvalidClearLevel);
+                ensure!(product.has_valid_order_limits(), Error::<T>::InvalidOrderLimits);
+                ensure!(product.has_valid_member_price(), Error::<T>::InvalidMemberPrice);
+                ensure!(product.has_valid_price_floor(), Error::<T>::InvalidPriceFloor);
+                ensure!(product.has_min_margin(T::MinMarginBasisPoints::get()), Error::<T>::MarginTooLow);
+                ensure!(product.has_valid_promo(), Error::<T>::InvalidPromo);
+                ensure!(!product_ids.contains(&product.id) && !Products::<T>::contains_key(product.id), Error::<T>::InvalidSnapshot);
+                product_ids.push(product.id);
+                match category_stock.iter_mut().find(|(category, _)| *category == product.category) {
+                    Some((_, stock)) => *stock = stock.saturating_add(product.stock),
+                    None => category_stock.push((product.category.clone(), product.stock))
+                }
+            }
+            for (category, stock) in category_stock.iter() {
+                Self::ensure_category_capacity(category, *stock)?;
+            }
+
+            // Validação das vendas
+            let mut sale_codes: Vec<u64> = Vec::new();
+            for sale in sales.iter() {
+                Self::validate_date(sale.date.clone())?;
+                ensure!(!sale_codes.contains(&sale.code) && !Sales::<T>::contains_key(sale.code), Error::<T>::InvalidSnapshot);
+                let known = |id: &u64| product_ids.contains(id) || Products::<T>::contains_key(id);
+                ensure!(sale.products.iter().all(known) && sale.lines.iter().all(|line| known(&line.product_id)), Error::<T>::InvalidSnapshot);
+                sale_codes.push(sale.code);
+            }
+
+            // Inserção dos produtos
+            for product in products {
+                Self::index_category(&product.category, product.id);
+                Self::record_price(product.id, product.price);
+                ProductCreatedAt::<T>::insert(product.id, frame_system::Pallet::<T>::block_number());
+                Self::increase_category_stock(&product.category, product.stock);
+                Self::update_restock_alert(product.id, &product);
+                OwnerProducts::<T>::insert(&owner, product.id, ());
+                ProductOwners::<T>::insert(product.id, owner.clone());
+                Products::<T>::insert(product.id, product);
+            }
+            // IDs importados deixam de ser reaproveitáveis
+            FreeProductIds::<T>::mutate(|free_ids| free_ids.retain(|free_id| !product_ids.contains(free_id)));
+
+            // Inserção das vendas, com os mesmos índices de `register_sale`, para que possam ser estornadas
+            for sale in sales {
+                DailyRevenue::<T>::mutate(&sale.date, |revenue| *revenue = revenue.saturating_add(sale.store_value() as u128));
+                Self::track_sale_value(sale.store_value())?;
+                for line in sale.lines.iter() {
+                    ProductSales::<T>::mutate(line.product_id, sale.code, |amount| *amount = amount.saturating_add(line.amount));
+                    CategoryRevenue::<T>::mutate(&line.category, |revenue| *revenue = revenue.saturating_add(line.store_value() as u128));
+                    UnitsSold::<T>::mutate(line.product_id, |units| *units = units.saturating_add(line.amount));
+                }
+                for id in sale.products.iter() {
+                    SaleTxCount::<T>::mutate(id, |count| *count = count.saturating_add(1));
+                }
+                Sales::<T>::insert(sale.code, sale);
+            }
+
+            // Contadores posicionados após os maiores IDs e códigos importados
+            if let Some(max_id) = product_ids.iter().max() {
+                NextProductId::<T>::put(Self::next_product_id().max(max_id.saturating_add(T::IdStep::get())));
+            }
+            if let Some(max_code) = sale_codes.iter().max() {
+                NextSaleCode::<T>::put(Self::next_sale_code().max(max_code.saturating_add(T::IdStep::get())));
+            }
+
+            Self::bump_catalog_version();
+            Self::bump_sales_version();
+            Self::deposit_event(Event::SnapshotImported(product_ids.len() as u32, sale_codes.len() as u32));
+
+            Ok(())
+        }
+
+        /// Função administrativa para definir ou remover (`None`) a alíquota de uma categoria tributária.
+        #[pallet::call_index(24)]
+        #[pallet::weight(10_000)]
+        pub fn set_tax_rate(origin: OriginFor<T>, tax_category: u8, rate: Option<u32>) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            match rate {
+                Some(rate) => TaxRates::<T>::insert(tax_category, rate),
+                None => TaxRates::<T>::remove(tax_category)
+            }
+
+            Self::deposit_event(Event::TaxRateSet(tax_category, rate));
+
+            Ok(())
+        }
+
+        /// Função administrativa para definir se os preços dos produtos já incluem o imposto.
+        #[pallet::call_index(49)]
+        #[pallet::weight(10_000)]
+        pub fn set_prices_include_tax(origin: OriginFor<T>, prices_include_tax: bool) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            PricesIncludeTax::<T>::put(prices_include_tax);
+            Self::deposit_event(Event::PricesIncludeTaxSet(prices_include_tax));
+
+            Ok(())
+        }
+
+        /// Função para criar um novo produto a partir de um produto existente, usado como modelo.
+        /// Todos os campos e o consignante são copiados, exceto o ID, o nome informado e o estoque, que começa zerado.
+        #[pallet::call_index(35)]
+        #[pallet::weight(10_000)]
+        pub fn clone_product(origin: OriginFor<T>, source_id: u64, new_name: Vec<u8>) -> DispatchResult {
+            let who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            let source = Products::<T>::get(source_id).ok_or(Error::<T>::ProductNotFound)?;
+
+            let new_id = Self::do_add_product(
+                who,
+                new_name,
+                0,
+                source.price,
+                source.amount_to_restock,
+                source.restock_date,
+                source.category,
+                source.metadata_hash,
+                None,
+                source.min_order_qty,
+                source.max_order_qty,
+                source.attributes,
+                source.unit
+            )?;
+
+            // Campos não aceitos na criação de produtos; o nível de encerramento é copiado junto com o ponto
+            // de reposição, que pode ser menor que a quantidade reposta usada como padrão
+            Products::<T>::mutate(new_id, |product| {
+                if let Some(product) = product {
+                    product.restock_clear_level = source.restock_clear_level;
+                    product.tax_category = source.tax_category;
+                    product.threshold_mode = source.threshold_mode;
+                    product.cost_price = source.cost_price;
+                    product.price_floor = source.price_floor;
+                    product.member_price = source.member_price;
+                    product.reorder_point = source.reorder_point;
+                    product.promo = source.promo;
+                }
+            });
+            if let Some(consignor) = Consignors::<T>::get(source_id) {
+                Consignors::<T>::insert(new_id, consignor);
+            }
+
+            Self::deposit_event(Event::ProductCloned(source_id, new_id));
+
+            Ok(())
+        }
+
+        /// Função administrativa para definir ou remover (`None`) o limite de estoque total de uma categoria.
+        /// O limite é verificado apenas em operações que aumentam o estoque da categoria.
+        #[pallet::call_index(42)]
+        #[pallet::weight(10_000)]
+        pub fn set_category_stock_cap(origin: OriginFor<T>, category: Category, cap: Option<u64>) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            match cap {
+                Some(cap) => CategoryStockCaps::<T>::insert(&category, cap),
+                None => CategoryStockCaps::<T>::remove(&category)
+            }
+
+            Self::deposit_event(Event::CategoryStockCapSet(category, cap));
+
+            Ok(())
+        }
+
+        /// Função administrativa para definir ou remover (`None`) a sobretaxa de um método de pagamento.
+        #[pallet::call_index(34)]
+        #[pallet::weight(10_000)]
+        pub fn set_payment_surcharge(origin: OriginFor<T>, payment_method: PaymentMethod, surcharge: Option<u32>) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            match surcharge {
+                Some(surcharge) => PaymentSurcharges::<T>::insert(&payment_method, surcharge),
+                None => PaymentSurcharges::<T>::remove(&payment_method)
+            }
+
+            Self::deposit_event(Event::PaymentSurchargeSet(payment_method, surcharge));
+
+            Ok(())
+        }
+
+        /// Função administrativa para pausar ou retomar as chamadas que alteram o catálogo ou as vendas.
+        /// Consultas continuam permitidas enquanto o pallet está pausado, assim como as chamadas de
+        /// manutenção (travar e destravar produtos, reparar os contadores de IDs) e as de configuração
+        /// (impostos, taxas, câmbio, limites de estoque e vales-presente), que não alteram produtos nem vendas.
+        #[pallet::call_index(26)]
+        #[pallet::weight(10_000)]
+        pub fn set_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            Paused::<T>::put(paused);
+            Self::deposit_event(Event::PausedSet(paused));
+
+            Ok(())
+        }
+
+        /// Função administrativa para travar um produto durante operações longas, como conciliações.
+        /// Enquanto travado, o produto não pode ser vendido, alterado, reposto, transferido, arquivado ou removido.
+        #[pallet::call_index(39)]
+        #[pallet::weight(10_000)]
+        pub fn lock_product(origin: OriginFor<T>, id: u64) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            ensure!(Products::<T>::contains_key(id), Error::<T>::ProductNotFound);
+            LockedProducts::<T>::insert(id, ());
+            Self::deposit_event(Event::ProductLockSet(id, true));
+
+            Ok(())
+        }
+
+        /// Função administrativa para destravar um produto.
+        #[pallet::call_index(40)]
+        #[pallet::weight(10_000)]
+        pub fn unlock_product(origin: OriginFor<T>, id: u64) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            LockedProducts::<T>::remove(id);
+            Self::deposit_event(Event::ProductLockSet(id, false));
+
+            Ok(())
+        }
+
+        /// Função para obter a receita total das vendas de uma categoria.
+        #[pallet::call_index(27)]
+        #[pallet::weight(10_000)]
+        pub fn get_category_revenue(origin: OriginFor<T>, category: Category) -> DispatchResult {
+            let _who = ensure_signed(origin)?;
+
+            let revenue = CategoryRevenue::<T>::get(&category);
+            Self::deposit_event(Event::CategoryRevenueGotten(category, revenue));
+
+            Ok(())
+        }
+
+        /// Função para definir a categoria tributária de um produto.
+        #[pallet::call_index(25)]
+        #[pallet::weight(10_000)]
+        pub fn set_product_tax_category(origin: OriginFor<T>, id: u64, tax_category: u8) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_not_locked(id)?;
+
+            Products::<T>::try_mutate(id, |product| -> DispatchResult {
+                let product = product.as_mut().ok_or(Error::<T>::ProductNotFound)?;
+                product.tax_category = tax_category;
+                Ok(())
+            })?;
+
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::ProductUpdated(id));
+
+            Ok(())
+        }
+
+        /// Função para definir como o limite de reposição de um produto é interpretado.
+        /// Em qualquer modo o limite vem do `reorder_point`; `amount_to_restock` segue sendo a quantidade reposta.
+        #[pallet::call_index(28)]
+        #[pallet::weight(10_000)]
+        pub fn set_threshold_mode(origin: OriginFor<T>, id: u64, threshold_mode: ThresholdMode) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_not_locked(id)?;
+
+            Products::<T>::try_mutate(id, |product| -> DispatchResult {
+                let product = product.as_mut().ok_or(Error::<T>::ProductNotFound)?;
+                product.threshold_mode = threshold_mode;
+                Self::update_restock_alert(id, product);
+                Ok(())
+            })?;
+
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::ProductUpdated(id));
+
+            Ok(())
+        }
+
+        /// Função para definir o ponto de reposição de um produto, independente da quantidade reposta.
+        /// O nível de encerramento do alerta, quando definido, não pode ficar abaixo do ponto de reposição.
+        #[pallet::call_index(36)]
+        #[pallet::weight(10_000)]
+        pub fn set_reorder_point(origin: OriginFor<T>, id: u64, reorder_point: u64) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_not_locked(id)?;
+
+            Products::<T>::try_mutate(id, |product| -> DispatchResult {
+                let product = product.as_mut().ok_or(Error::<T>::ProductNotFound)?;
+                product.reorder_point = reorder_point;
+                ensure!(product.has_valid_clear_level(), Error::<T>::InvalidClearLevel);
+                Self::update_restock_alert(id, product);
+                Ok(())
+            })?;
+
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::ProductUpdated(id));
+
+            Ok(())
+        }
+
+        /// Função para definir ou remover (`None`) o preço para membros de um produto.
+        /// O preço para membros não pode ser maior que o preço padrão.
+        #[pallet::call_index(43)]
+        #[pallet::weight(10_000)]
+        pub fn set_member_price(origin: OriginFor<T>, id: u64, member_price: Option<u64>) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_not_locked(id)?;
+
+            Products::<T>::try_mutate(id, |product| -> DispatchResult {
+                let product = product.as_mut().ok_or(Error::<T>::ProductNotFound)?;
+                product.member_price = member_price;
+                ensure!(product.has_valid_member_price(), Error::<T>::InvalidMemberPrice);
+                Ok(())
+            })?;
+
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::MemberPriceSet(id, member_price));
+
+            Ok(())
+        }
+
+        /// Função para definir ou remover (`None`) o custo de um produto.
+        /// Com o custo informado, o preço do produto precisa manter a margem mínima `MinMarginBasisPoints`.
+        #[pallet::call_index(52)]
+        #[pallet::weight(10_000)]
+        pub fn set_cost_price(origin: OriginFor<T>, id: u64, cost_price: Option<u64>) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_not_locked(id)?;
+
+            Products::<T>::try_mutate(id, |product| -> DispatchResult {
+                let product = product.as_mut().ok_or(Error::<T>::ProductNotFound)?;
+                product.cost_price = cost_price;
+                ensure!(product.has_min_margin(T::MinMarginBasisPoints::get()), Error::<T>::MarginTooLow);
+                Ok(())
+            })?;
+
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::CostPriceSet(id, cost_price));
+
+            Ok(())
+        }
+
+        /// Função para definir ou remover (`None`) o consignante de um produto em consignação.
+        /// Nas vendas do produto, o custo das unidades vendidas é pago pelo comprador ao consignante e descontado
+        /// do que é pago à loja; no estorno da venda, o consignante devolve o repasse ao comprador.
+        #[pallet::call_index(55)]
+        #[pallet::weight(10_000)]
+        pub fn set_consignor(origin: OriginFor<T>, id: u64, consignor: Option<T::AccountId>) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_not_locked(id)?;
+
+            ensure!(Products::<T>::contains_key(id), Error::<T>::ProductNotFound);
+            match &consignor {
+                Some(consignor) => Consignors::<T>::insert(id, consignor),
+                None => Consignors::<T>::remove(id)
+            }
+
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::ConsignorSet(id, consignor));
+
+            Ok(())
+        }
+
+        /// Função para validar um carrinho antes da venda, com as mesmas verificações de `register_sale`
+        /// sobre os itens. Nada é gravado além do evento com o resultado: `CartValid` com o total ou,
+        /// em caso de falha, `CartItemRejected` com o produto rejeitado e o erro, precedido de
+        /// `SaleValidationFailed` quando falta estoque ou de `SaleComputationOverflow` quando o total
+        /// estoura. A chamada não falha por causa do carrinho, para que os eventos não sejam descartados;
+        /// por isso o diagnóstico é emitido aqui, e não em `register_sale`.
+        #[pallet::call_index(56)]
+        #[pallet::weight(10_000)]
+        pub fn validate_cart(origin: OriginFor<T>, items: Vec<ItemSale>) -> DispatchResult {
+            let _who = T::CustomerOrigin::ensure_origin(origin)?;
+
+            let event = match Self::check_cart(items, false) {
+                Ok(check) => Event::CartValid(check.total_value),
+                Err(rejection) => {
+                    // Diagnóstico identificando o item sem estoque suficiente
+                    if rejection.error == DispatchError::from(Error::<T>::InsufficientStock) {
+                        Self::deposit_event(Event::SaleValidationFailed(rejection.product_id, rejection.requested, rejection.available));
+                    }
+                    // Diagnóstico identificando o item em que a soma estourou
+                    if rejection.error == DispatchError::from(Error::<T>::Overflow) {
+                        Self::deposit_event(Event::SaleComputationOverflow(rejection.product_id, rejection.running_total));
+                    }
+                    Event::CartItemRejected(rejection.product_id, rejection.error)
+                }
+            };
+            Self::deposit_event(event);
+
+            Ok(())
+        }
+
+        /// Função para definir um preço relâmpago para o produto, válido até o bloco `until` (inclusive),
+        /// sem alterar o preço base. Enquanto valer, as vendas do produto usam esse preço.
+        #[pallet::call_index(57)]
+        #[pallet::weight(10_000)]
+        pub fn set_flash_price(origin: OriginFor<T>, id: u64, price: u64, until: BlockNumberFor<T>) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_not_locked(id)?;
+
+            ensure!(Products::<T>::contains_key(id), Error::<T>::ProductNotFound);
+            ensure!(until >= frame_system::Pallet::<T>::block_number(), Error::<T>::FlashPriceExpired);
+
+            FlashExpiries::<T>::try_mutate(until.saturating_add(1u32.into()), |ids| -> DispatchResult {
+                if !ids.contains(&id) {
+                    ids.try_push(id).map_err(|_| Error::<T>::TooManyFlashExpiries)?;
+                }
+                Ok(())
+            })?;
+            FlashPrices::<T>::insert(id, (price, until));
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::FlashPriceSet(id, price, until));
+
+            Ok(())
+        }
+
+        /// Função para definir ou remover (`None`) o piso de preço de um produto. Nas vendas, o valor de
+        /// cada item não fica abaixo do piso, qualquer que seja o desconto, preço relâmpago ou preço de membro.
+        #[pallet::call_index(58)]
+        #[pallet::weight(10_000)]
+        pub fn set_price_floor(origin: OriginFor<T>, id: u64, price_floor: Option<u64>) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_not_locked(id)?;
+
+            Products::<T>::try_mutate(id, |product| -> DispatchResult {
+                let product = product.as_mut().ok_or(Error::<T>::ProductNotFound)?;
+                product.price_floor = price_floor;
+                ensure!(product.has_valid_price_floor(), Error::<T>::InvalidPriceFloor);
+                Ok(())
+            })?;
+
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::PriceFloorSet(id, price_floor));
+
+            Ok(())
+        }
+
+        /// Função administrativa para transferir a responsabilidade por todos os produtos para `new_owner`.
+        /// Cada chamada percorre no máximo `MaxPageSize` produtos; enquanto `TransferCursor` estiver
+        /// definido, novas chamadas continuam de onde a anterior parou, e apenas para o mesmo destino.
+        #[pallet::call_index(59)]
+        #[pallet::weight(10_000)]
+        pub fn transfer_all_products(origin: OriginFor<T>, new_owner: T::AccountId) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            let cursor = match TransferCursor::<T>::get() {
+                Some((owner, cursor)) => {
+                    ensure!(owner == new_owner, Error::<T>::TransferInProgress);
+                    Some(cursor)
+                }
+                None => None
+            };
+            let (page, next) = Self::products_page(cursor, T::MaxPageSize::get());
+
+            let mut count: u32 = 0;
+            for (id, _) in page {
+                if let Some(owner) = ProductOwners::<T>::get(id) {
+                    OwnerProducts::<T>::remove(&owner, id);
+                }
+                OwnerProducts::<T>::insert(&new_owner, id, ());
+                ProductOwners::<T>::insert(id, &new_owner);
+                count += 1;
+            }
+
+            match next {
+                Some(cursor) => TransferCursor::<T>::put((new_owner.clone(), cursor)),
+                None => TransferCursor::<T>::kill()
+            }
+
+            if count > 0 {
+                Self::bump_catalog_version();
+            }
+            Self::deposit_event(Event::CatalogTransferred(new_owner, count));
+
+            Ok(())
+        }
+
+        /// Função para definir ou remover (`None`) a promoção de um produto, aplicada às vendas
+        /// registradas entre o início e o fim da promoção (inclusive), conforme a data on-chain atual.
+        /// A data informada na venda é apenas registrada e não altera a elegibilidade.
+        #[pallet::call_index(45)]
+        #[pallet::weight(10_000)]
+        pub fn set_promo(origin: OriginFor<T>, id: u64, promo: Option<(Date, Date, u8)>) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_not_locked(id)?;
+
+            let promo = match promo {
+                Some((start, end, percent)) => Some((Self::validate_date(start)?, Self::validate_date(end)?, percent)),
+                None => None
+            };
+
+            Products::<T>::try_mutate(id, |product| -> DispatchResult {
+                let product = product.as_mut().ok_or(Error::<T>::ProductNotFound)?;
+                product.promo = promo.clone();
+                ensure!(product.has_valid_promo(), Error::<T>::InvalidPromo);
+                Ok(())
+            })?;
+
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::PromoSet(id, promo));
+
+            Ok(())
+        }
+
+        /// Função para repor o estoque de um produto, somando `amount_to_restock` ao estoque atual.
+        #[pallet::call_index(37)]
+        #[pallet::weight(10_000)]
+        pub fn restock_product(origin: OriginFor<T>, id: u64) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            let mut product = Products::<T>::get(id).ok_or(Error::<T>::ProductNotFound)?;
+            Self::ensure_not_locked(id)?;
+            Self::ensure_category_capacity(&product.category, product.amount_to_restock)?;
+            product.stock = product.stock.checked_add(product.amount_to_restock).ok_or(Error::<T>::Overflow)?;
+
+            Self::increase_category_stock(&product.category, product.amount_to_restock);
+            Self::update_restock_alert(id, &product);
+            let stock = product.stock;
+            Products::<T>::insert(id, product);
+
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::ProductRestocked(id, stock));
+
+            Ok(())
+        }
+
+        /// Função administrativa para somar um valor (positivo ou negativo) ao preço de todos os produtos.
+        /// Se algum preço ficasse negativo, estourasse, ficasse abaixo do piso de preço ou do preço para membros,
+        /// ou deixasse de atingir a margem mínima, nenhum produto é alterado.
+        #[pallet::call_index(29)]
+        #[pallet::weight(10_000)]
+        pub fn adjust_all_prices(origin: OriginFor<T>, delta: i64) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            // Cálculo de todos os novos preços antes de qualquer escrita
+            let mut updated: Vec<(u64, ProductOf<T>)> = Vec::new();
+            for (id, mut product) in Products::<T>::iter() {
+                Self::ensure_not_locked(id)?;
+                let price = product.price.checked_add_signed(delta).ok_or(Error::<T>::Overflow)?;
+                product.price = Self::round_to_increment(price);
+                ensure!(product.has_valid_price_floor(), Error::<T>::InvalidPriceFloor);
+                ensure!(product.has_valid_member_price(), Error::<T>::InvalidMemberPrice);
+                ensure!(product.has_min_margin(T::MinMarginBasisPoints::get()), Error::<T>::MarginTooLow);
+                updated.push((id, product));
+            }
+
+            let count = updated.len() as u32;
+            for (id, product) in updated {
+                Self::record_price(id, product.price);
+                Products::<T>::insert(id, product);
+            }
+
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::AllPricesAdjusted(count, delta));
+
+            Ok(())
+        }
+
+        /// Função para listar os IDs dos produtos sem vendas nos últimos `idle_blocks` blocos,
+        /// incluindo os que nunca foram vendidos.
+        #[pallet::call_index(30)]
+        #[pallet::weight(10_000)]
+        pub fn list_dead_stock(origin: OriginFor<T>, idle_blocks: u32, limit: u32) -> DispatchResult {
+            let _who = ensure_signed(origin)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let idle_blocks: BlockNumberFor<T> = idle_blocks.into();
+
+            let mut ids: Vec<u64> = Products::<T>::iter_keys()
+                .filter(|id| match LastSoldAt::<T>::get(id) {
+                    Some(sold_at) => now.saturating_sub(sold_at) > idle_blocks,
+                    None => true
+                })
+                .collect();
+            ids.sort();
+            ids.truncate(Self::page_size(limit));
+
+            Self::deposit_event(Event::DeadStockListed(ids));
+
+            Ok(())
+        }
+
+        /// Função administrativa para arquivar os produtos com estoque menor ou igual a `level`, exceto os travados.
+        /// Cada chamada percorre no máximo `MaxPageSize` produtos; enquanto `ArchiveCursor` estiver
+        /// definido, novas chamadas continuam de onde a anterior parou.
+        #[pallet::call_index(31)]
+        #[pallet::weight(10_000)]
+        pub fn archive_below_stock(origin: OriginFor<T>, level: u64) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            let (page, next) = Self::products_page(ArchiveCursor::<T>::get(), T::MaxPageSize::get());
+
+            let mut count: u32 = 0;
+            for (id, product) in page {
+                // Produtos travados não são arquivados
+                if product.stock <= level && !LockedProducts::<T>::contains_key(id) {
+                    Self::archive_product(id, product);
+                    count += 1;
+                }
+            }
+
+            match next {
+                Some(cursor) => ArchiveCursor::<T>::put(cursor),
+                None => ArchiveCursor::<T>::kill()
+            }
+
+            // Uma página sem produtos arquivados não altera o catálogo
+            if count > 0 {
+                Self::bump_catalog_version();
+            }
+            Self::deposit_event(Event::BulkArchived(count));
+
+            Ok(())
+        }
+
+        /// Função para transferir a responsabilidade por um produto para outra conta.
+        /// Apenas a conta responsável atual pode transferir o produto.
+        #[pallet::call_index(32)]
+        #[pallet::weight(10_000)]
+        pub fn transfer_product(origin: OriginFor<T>, id: u64, new_owner: T::AccountId) -> DispatchResult {
+            let who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+            Self::ensure_not_locked(id)?;
+
+            ensure!(Products::<T>::contains_key(id), Error::<T>::ProductNotFound);
+            ensure!(ProductOwners::<T>::get(id).as_ref() == Some(&who), Error::<T>::NotProductOwner);
+
+            OwnerProducts::<T>::remove(&who, id);
+            OwnerProducts::<T>::insert(&new_owner, id, ());
+            ProductOwners::<T>::insert(id, &new_owner);
+
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::ProductTransferred(id, who, new_owner));
+
+            Ok(())
+        }
+
+        /// Função para listar os IDs dos produtos da conta que faz a chamada.
+        #[pallet::call_index(33)]
+        #[pallet::weight(10_000)]
+        pub fn list_my_products(origin: OriginFor<T>, limit: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let mut ids: Vec<u64> = OwnerProducts::<T>::iter_key_prefix(&who).collect();
+            ids.sort();
+            ids.truncate(Self::page_size(limit));
+
+            Self::deposit_event(Event::OwnerProductsListed(who, ids));
+
+            Ok(())
+        }
+
+        /// Função para transferir estoque entre dois produtos (ex.: variações de tamanho ou cor).
+        /// Nenhum dos produtos é alterado caso a transferência falhe.
+        #[pallet::call_index(12)]
+        #[pallet::weight(10_000)]
+        pub fn transfer_stock(origin: OriginFor<T>, from_id: u64, to_id: u64, amount: u64) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(from_id != to_id, Error::<T>::SameProduct);
+
+            let mut from = Products::<T>::get(from_id).ok_or(Error::<T>::ProductNotFound)?;
+            let mut to = Products::<T>::get(to_id).ok_or(Error::<T>::ProductNotFound)?;
+            Self::ensure_not_locked(from_id)?;
+            Self::ensure_not_locked(to_id)?;
+
+            // Cálculo dos novos estoques antes de qualquer escrita
+            from.stock = from.stock.checked_sub(amount).ok_or(Error::<T>::InsufficientStock)?;
+            to.stock = to.stock.checked_add(amount).ok_or(Error::<T>::Overflow)?;
+            if from.category != to.category {
+                Self::ensure_category_capacity(&to.category, amount)?;
+            }
+
+            Self::decrease_category_stock(&from.category, amount);
+            Self::increase_category_stock(&to.category, amount);
+            Self::update_restock_alert(from_id, &from);
+            Self::update_restock_alert(to_id, &to);
+            Products::<T>::insert(from_id, from);
+            Products::<T>::insert(to_id, to);
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::StockTransferred(from_id, to_id, amount));
+
+            Ok(())
+        }
+
+        /// Função administrativa para reparar o contador de IDs de produtos.
+        /// Ajusta `NextProductId` para além do maior ID armazenado, evitando colisões após inserções manuais.
+        #[pallet::call_index(13)]
+        #[pallet::weight(10_000)]
+        pub fn repair_next_product_id(origin: OriginFor<T>) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let next_id = match Products::<T>::iter_keys().max() {
+                Some(max_id) => max_id.checked_add(T::IdStep::get()).ok_or(Error::<T>::Overflow)?,
+                None => 0
+            };
+
+            // O contador nunca é reduzido, para não reutilizar IDs já referenciados por vendas
+            let next_id = next_id.max(Self::next_product_id());
+            NextProductId::<T>::put(next_id);
+            Self::deposit_event(Event::ProductCounterRepaired(next_id));
+
+            Ok(())
+        }
+
+        /// Função administrativa para reparar o contador de códigos de vendas.
+        #[pallet::call_index(14)]
+        #[pallet::weight(10_000)]
+        pub fn repair_next_sale_code(origin: OriginFor<T>) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let next_code = match Sales::<T>::iter_keys().max() {
+                Some(max_code) => max_code.checked_add(T::IdStep::get()).ok_or(Error::<T>::Overflow)?,
+                None => 0
+            };
+
+            let next_code = next_code.max(Self::next_sale_code());
+            NextSaleCode::<T>::put(next_code);
+            Self::deposit_event(Event::SaleCounterRepaired(next_code));
+
+            Ok(())
+        }
+
+        /// Função administrativa para redefinir o contador de códigos de vendas, por exemplo após a remoção
+        /// de vendas antigas. Diferente de `repair_next_sale_code`, permite reduzir o contador, desde que
+        /// o novo valor seja maior que todos os códigos de vendas armazenados.
+        #[pallet::call_index(44)]
+        #[pallet::weight(10_000)]
+        pub fn reset_sale_code(origin: OriginFor<T>, value: u64) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            if let Some(max_code) = Sales::<T>::iter_keys().max() {
+                ensure!(value > max_code, Error::<T>::CodeWouldCollide);
+            }
+
+            NextSaleCode::<T>::put(value);
+            Self::deposit_event(Event::SaleCodeReset(value));
+
+            Ok(())
+        }
+
+        /// Função para listar os produtos com estoque abaixo de um nível informado, limitada a `limit` produtos.
+        /// Diferente de `list_products_to_restock`, ignora o limite de reposição de cada produto.
+        #[pallet::call_index(15)]
+        #[pallet::weight(10_000)]
+        pub fn list_products_below_stock(origin: OriginFor<T>, level: u64, limit: u32) -> DispatchResult {
+            let _who = ensure_signed(origin)?;
+
+            let products: Vec<ProductOf<T>> = Products::<T>::iter()
+                .map(|(_, product)| product)
+                .filter(|product| product.stock < level)
+                .take(Self::page_size(limit))
+                .collect();
+
+            if T::EmitListingPayloads::get() {
+                Self::deposit_event(Event::ProductsBelowStock(products));
+            } else {
+                Self::deposit_event(Event::ProductsBelowStockCount(products.len() as u32));
+            }
+
+            Ok(())
+        }
+
+        /// Função para listar os IDs e preços dos produtos ordenados por preço, desempatando pelo menor ID,
+        /// limitada a `limit` produtos. Como o mapa de produtos não é ordenado, todo o catálogo é lido e
+        /// ordenado, com custo O(n log n); o peso cresce com a quantidade de produtos.
+        #[pallet::call_index(47)]
+        #[pallet::weight(T::DbWeight::get().reads(Products::<T>::count() as u64).saturating_add(Weight::from_parts(10_000, 0)))]
+        pub fn list_products_by_price(origin: OriginFor<T>, ascending: bool, limit: u32) -> DispatchResult {
+            let _who = ensure_signed(origin)?;
+
+            let mut products: Vec<(u64, u64)> = Products::<T>::iter()
+                .filter(|(_, product)| Self::is_listable(product))
+                .map(|(id, product)| (id, product.price))
+                .collect();
+            products.sort_by(|a, b| {
+                let by_price = if ascending { a.1.cmp(&b.1) } else { b.1.cmp(&a.1) };
+                by_price.then_with(|| a.0.cmp(&b.0))
+            });
+            products.truncate(Self::page_size(limit));
+
+            Self::deposit_event(Event::ProductsByPrice(products));
+
+            Ok(())
+        }
+
+        /// Função para listar o código e o valor das `n` vendas mais recentes, da mais nova para a mais antiga,
+        /// limitada a `MaxPageSize` vendas. Como os códigos sequenciais seguem a ordem de criação, as vendas
+        /// são buscadas a partir de `NextSaleCode`, sem percorrer todo o mapa. A busca segue até reunir `n` vendas
+        /// ou esgotar os códigos, consultando no máximo `MaxRecentSalesScan` códigos; apenas quando há mais vendas
+        /// removidas do que esse limite entre as recentes o resultado fica menor que `n`. O peso declarado cobre
+        /// o limite e as consultas não feitas são devolvidas.
+        /// Com `HashedSaleCodes`, os códigos não seguem a ordem de criação e nada é listado.
+        #[pallet::call_index(51)]
+        #[pallet::weight(T::DbWeight::get().reads(T::MaxRecentSalesScan::get() as u64 + 1).saturating_add(Weight::from_parts(10_000, 0)))]
+        pub fn recent_sales(origin: OriginFor<T>, n: u32) -> DispatchResultWithPostInfo {
+            let _who = ensure_signed(origin)?;
+
+            let limit = Self::page_size(n);
+            let step = T::IdStep::get().max(1);
+            let mut sales: Vec<(u64, u64)> = Vec::new();
+            let mut next = Self::next_sale_code();
+            let mut scanned: u32 = 0;
+            while sales.len() < limit && scanned < T::MaxRecentSalesScan::get() {
+                let Some(code) = next.checked_sub(step) else { break };
+                scanned += 1;
+                if let Some(sale) = Sales::<T>::get(code) {
+                    sales.push((code, sale.value));
+                }
+                next = code;
+            }
+
+            Self::deposit_event(Event::RecentSales(sales));
+
+            Ok(Some(T::DbWeight::get().reads(scanned as u64 + 1).saturating_add(Weight::from_parts(10_000, 0))).into())
+        }
+
+        /// Função para definir a data de reposição de todos os produtos de uma categoria, usando o índice
+        /// de categorias. Produtos travados são mantidos como estão e não entram na contagem do evento.
+        #[pallet::call_index(53)]
+        #[pallet::weight(10_000)]
+        pub fn set_category_restock_date(origin: OriginFor<T>, category: Category, date: Date) -> DispatchResult {
+            let _who = T::StaffOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            let date = Self::validate_date(date)?;
+            Self::ensure_restock_date_not_past(&date)?;
+
+            let mut count: u32 = 0;
+            for id in ProductsByCategory::<T>::iter_key_prefix(&category) {
+                if LockedProducts::<T>::contains_key(id) {
+                    continue;
+                }
+                Products::<T>::mutate(id, |product| {
+                    if let Some(product) = product {
+                        product.restock_date = date.clone();
+                        count = count.saturating_add(1);
+                    }
+                });
+            }
+
+            if count > 0 {
+                Self::bump_catalog_version();
+            }
+            Self::deposit_event(Event::CategoryRestockDateSet(category, count));
+
+            Ok(())
+        }
+
+        /// Função administrativa para mover todos os produtos de uma categoria para outra.
+        /// Produtos travados permanecem na categoria de origem e não entram na contagem do evento.
+        /// Falha se o estoque movido ultrapassar o limite de estoque da categoria de destino.
+        #[pallet::call_index(16)]
+        #[pallet::weight(10_000)]
+        pub fn reassign_category(origin: OriginFor<T>, from: Category, to: Category) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused()?;
+
+            ensure!(from != to, Error::<T>::SameCategory);
+
+            let products: Vec<(u64, ProductOf<T>)> = ProductsByCategory::<T>::iter_key_prefix(&from)
+                .filter(|id| !LockedProducts::<T>::contains_key(id))
+                .filter_map(|id| Products::<T>::get(id).map(|product| (id, product)))
+                .collect();
+            let moved_stock = products.iter().fold(0u64, |total, (_, product)| total.saturating_add(product.stock));
+            Self::ensure_category_capacity(&to, moved_stock)?;
+
+            for (id, mut product) in products.iter().cloned() {
+                Self::unindex_category(&from, id);
+                product.category = to.clone();
+                Products::<T>::insert(id, product);
+                Self::index_category(&to, id);
+            }
+            Self::decrease_category_stock(&from, moved_stock);
+            Self::increase_category_stock(&to, moved_stock);
+
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::CategoryReassigned(from, to, products.len() as u32));
+
+            Ok(())
+        }
+
+        /// Função para listar os `n` compradores que mais gastaram, do maior para o menor gasto.
+        #[pallet::call_index(17)]
+        #[pallet::weight(10_000)]
+        pub fn top_buyers(origin: OriginFor<T>, n: u32) -> DispatchResult {
+            let _who = ensure_signed(origin)?;
+
+            let mut buyers: Vec<(T::AccountId, u128)> = BuyerSpend::<T>::iter().filter(|(_, spend)| *spend > 0).collect();
+            buyers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            buyers.truncate(Self::page_size(n));
+
+            Self::deposit_event(Event::TopBuyers(buyers));
+
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Adiciona um novo produto pertencente a `owner` e retorna o ID gerado.
+        #[allow(clippy::too_many_arguments)]
+        fn do_add_product(owner: T::AccountId, name: Vec<u8>, stock: u64, price: u64, amount_to_restock: u64, restock_date: Date, category: Category, metadata_hash: Option<[u8; 32]>, restock_clear_level: Option<u64>, min_order_qty: Option<u64>, max_order_qty: Option<u64>, attributes: Attributes<T::MaxAttributes>, unit: StockUnitLabel) -> Result<u64, DispatchError> {
+
+            // Validação da data
+            let restock_date = Self::validate_date(restock_date)?;
+            Self::ensure_restock_date_not_past(&restock_date)?;
+
+            ensure!(amount_to_restock <= T::MaxRestockAmount::get(), Error::<T>::RestockAmountTooLarge);
+
+            // Próximo ID sequencial, avançando conforme `IdStep`
+            let next_id = Self::next_product_id().checked_add(T::IdStep::get()).ok_or(Error::<T>::Overflow)?;
+
+            // Reutilização de um ID livre, quando habilitada
+            let recycled_id = if T::RecycleIds::get() {
+                FreeProductIds::<T>::mutate(|free_ids| free_ids.pop())
+            } else {
+                None
+            };
+            let product_id = recycled_id.unwrap_or_else(Self::next_product_id);
+
+            let product = Product {
+                name,
+                id: product_id,
+                stock,
+                price,
+                amount_to_restock,
+                reorder_point: amount_to_restock,
+                restock_date,
+                category,
+                metadata_hash,
+                restock_clear_level,
+                tax_category: 0,
+                threshold_mode: ThresholdMode::Units,
+                min_order_qty,
+                max_order_qty,
+                member_price: None,
+                promo: None,
+                attributes,
+                cost_price: None,
+                unit,
+                price_floor: None
+            };
+
+            ensure!(product.has_valid_clear_level(), Error::<T>::InvalidClearLevel);
+            ensure!(product.accepts_quantity(product.stock, T::StockUnit::get()), Error::<T>::FractionalQuantity);
+            ensure!(product.has_valid_order_limits(), Error::<T>::InvalidOrderLimits);
+            Self::ensure_category_capacity(&product.category, product.stock)?;
+
+            // Primeiro produto da categoria, identificado pelo índice ainda vazio
+            let first_in_category = ProductsByCategory::<T>::iter_key_prefix(&product.category).next().is_none();
+
+            // Inserção do produto no armazenamento
+            Self::index_category(&product.category, product_id);
+            Self::increase_category_stock(&product.category, product.stock);
+            Products::<T>::insert(product_id, &product);
+            if recycled_id.is_none() {
+                NextProductId::<T>::put(next_id);
+            }
+
+            Self::record_price(product_id, product.price);
+            ProductCreatedAt::<T>::insert(product_id, frame_system::Pallet::<T>::block_number());
+            OwnerProducts::<T>::insert(&owner, product_id, ());
+            ProductOwners::<T>::insert(product_id, owner);
+
+            // Emissão do evento
+            if first_in_category {
+                Self::deposit_event(Event::NewCategoryProduct(product.category.clone(), product_id));
+            }
+            Self::bump_catalog_version();
+            Self::deposit_event_indexed(Event::ProductAdded(product_id), &[Self::product_topic(product_id)]);
+            Self::update_restock_alert(product_id, &product);
+
+            Ok(product_id)
+        }
+
+        /// Valida os itens de uma venda e calcula seus valores, sem escrever no armazenamento.
+        /// Em caso de falha, retorna também o ID do produto do item rejeitado.
+        fn check_cart(products: Vec<ItemSale>, is_member: bool) -> Result<CartCheck<T>, CartRejection> {
+            let prices_include_tax = Self::prices_include_tax();
+            // Promoções valem conforme a data atual, não a data informada na venda
+            let today = Self::today();
+            let mut check = CartCheck::<T>::default();
+            // Total acumulado em u128, convertido para u64 apenas ao final
+            let mut total_value: u128 = 0;
+            for item in products {
+                let (product_id, requested, running_total) = (item.product_id, item.amount, total_value as u64);
+                Self::check_cart_item(item, &today, is_member, prices_include_tax, &mut total_value, &mut check)
+                    .map_err(|error| {
+                        // Um item rejeitado por falta de estoque ainda não descontou o estoque do produto
+                        let available = check.updated.iter().find(|(id, _)| *id == product_id).map_or(0, |(_, product)| product.stock);
+                        CartRejection { product_id, error, requested, available, running_total }
+                    })?;
+            }
+            // A soma é limitada a `u64::MAX` na validação de cada item
+            check.total_value = total_value as u64;
+            if T::TaxOnSaleTotal::get() {
+                check.total_tax = Self::allocate_sale_tax(&mut check.lines, check.scaled_tax);
+            }
+            Ok(check)
+        }
+
+        /// Valida um item da venda, acumulando seus valores em `check`.
+        fn check_cart_item(item: ItemSale, today: &Date, is_member: bool, prices_include_tax: bool, total_value: &mut u128, check: &mut CartCheck<T>) -> DispatchResult {
+            let position = match check.updated.iter().position(|(id, _)| *id == item.product_id) {
+                Some(position) => position,
+                None => {
+                    let product = Products::<T>::get(item.product_id).ok_or(Error::<T>::ProductNotFound)?;
+                    Self::ensure_not_locked(item.product_id)?;
+                    ensure!(Self::is_listable(&product), Error::<T>::ProductNotListable);
+                    check.updated.push((item.product_id, product));
+                    check.updated.len() - 1
+                }
+            };
+            let product = &mut check.updated[position].1;
+
+            ensure!(item.amount > 0, Error::<T>::InvalidQuantity);
+            ensure!(product.accepts_quantity(item.amount, T::StockUnit::get()), Error::<T>::FractionalQuantity);
+
+            // Limites de quantidade por item
+            ensure!(product.min_order_qty.map_or(true, |min| item.amount >= min), Error::<T>::QuantityBelowMinimum);
+            ensure!(product.max_order_qty.map_or(true, |max| item.amount <= max), Error::<T>::QuantityAboveMaximum);
+
+            ensure!(product.stock >= item.amount || T::AllowOversell::get(), Error::<T>::InsufficientStock);
+            let deficit = item.amount.saturating_sub(product.stock);
+            if deficit > 0 {
+                check.backorders.push((item.product_id, deficit));
+            }
+            check.category_stock_taken.push((product.category.clone(), item.amount.min(product.stock)));
+            product.stock = product.stock.saturating_sub(item.amount);
+
+            // O preço relâmpago vigente substitui o preço base; membros pagam o menor entre ele e o preço de membro
+            let base_price = Self::flash_price(item.product_id).unwrap_or(product.price);
+            let unit_price = if is_member { product.member_price.map_or(base_price, |price| price.min(base_price)) } else { base_price };
+            // Entre o desconto do item e o da promoção vigente hoje, vale o maior
+            let discount_percent = item.discount_percent.max(product.promo_discount(today));
+            let (partial_value, floored) = Self::floored_line_value(unit_price, item.amount, discount_percent, product.price_floor)?;
+            let total = *total_value + partial_value as u128;
+            ensure!(total <= u64::MAX as u128, Error::<T>::Overflow);
+            if floored {
+                check.floored.push(item.product_id);
+            }
+            *total_value = total;
+
+            // Imposto do item conforme a categoria tributária do produto
+            let line_tax = Self::line_tax(partial_value, product.tax_category, prices_include_tax)?;
+            check.total_tax = check.total_tax.checked_add(line_tax).ok_or(Error::<T>::Overflow)?;
+            check.scaled_tax = check.scaled_tax.saturating_add(Self::scaled_line_tax(partial_value, product.tax_category, prices_include_tax));
+
+            // Parcela de custo do item em consignação, fora da receita da loja
+            let consigned = match (Consignors::<T>::get(item.product_id), product.cost_price) {
+                (Some(consignor), Some(cost_price)) => {
+                    let cost = Self::line_value(cost_price, item.amount, None)?;
+                    match check.consignments.iter_mut().find(|(account, _)| *account == consignor) {
+                        Some((_, owed)) => *owed = owed.checked_add(cost).ok_or(Error::<T>::Overflow)?,
+                        None => check.consignments.push((consignor, cost))
+                    }
+                    cost
+                }
+                _ => 0
+            };
+
+            check.lines.push(SaleLine {
+                product_id: item.product_id,
+                amount: item.amount,
+                unit_price,
+                discount_percent,
+                price_floor: product.price_floor,
+                value: partial_value,
+                category: product.category.clone(),
+                tax: line_tax,
+                consigned
+            });
+
+            Ok(())
+        }
+
+        /// Registra uma venda para o comprador `who` e retorna o código gerado.
+        /// Quando `is_member` é verdadeiro, os produtos com preço para membros são vendidos por esse preço.
+        /// Sem `split`, o total não coberto pelo cartão-presente é pago integralmente com `payment_method`.
+        #[allow(clippy::too_many_arguments)]
+        fn do_register_sale(who: T::AccountId, seller: Vec<u8>, products: Vec<ItemSale>, bundles: Vec<u64>, payment_method: PaymentMethod, split: Option<Vec<(PaymentMethod, u64)>>, date: Date, gift_card: Option<[u8; 16]>, currency: Option<[u8; 3]>, is_member: bool) -> Result<u64, DispatchError> {
+            let sales_this_block = Self::sales_this_block();
+            ensure!(sales_this_block < T::MaxSalesPerBlock::get(), Error::<T>::SaleRateLimited);
+
+            // Geração do código da venda, sequencial ou derivado do conteúdo
+            let sale_code = if T::HashedSaleCodes::get() {
+                let block_number = frame_system::Pallet::<T>::block_number();
+                let hash = (&seller, &products, &bundles, block_number).blake2_256();
+                u64::from_le_bytes([hash[0], hash[1], hash[2], hash[3], hash[4], hash[5], hash[6], hash[7]])
+            } else {
+                Self::next_sale_code()
+            };
+            ensure!(!Sales::<T>::contains_key(sale_code), Error::<T>::SaleCodeCollision);
+            // Próximo código sequencial, avançando conforme `IdStep`
+            let next_sale_code = if T::HashedSaleCodes::get() {
+                None
+            } else {
+                Some(sale_code.checked_add(T::IdStep::get()).ok_or(Error::<T>::Overflow)?)
+            };
+
+            // Validação da data
+            let date = Self::validate_date(date)?;
+
+            // Validação do cartão-presente
+            let gift_card_balance = match gift_card {
+                Some(card) => Some((card, GiftCards::<T>::get(card).ok_or(Error::<T>::InvalidGiftCard)?)),
+                None => None
+            };
+
+            // Validação da moeda de exibição
+            let exchange_rate = match currency {
+                Some(code) => Some((code, ExchangeRates::<T>::get(code).ok_or(Error::<T>::UnknownCurrency)?)),
+                None => None
+            };
+
+            // Expansão dos kits em seus produtos componentes
+            let mut products = products;
+            for bundle_id in bundles {
+                let components = Bundles::<T>::get(bundle_id).ok_or(Error::<T>::BundleNotFound)?;
+                products.extend(components);
+            }
+
+            // Validação de todos os itens antes de qualquer escrita
+            let prices_include_tax = Self::prices_include_tax();
+            let CartCheck { total_value, total_tax, lines, updated, backorders, category_stock_taken, consignments, floored } =
+                Self::check_cart(products, is_member).map_err(|rejection| rejection.error)?;
+
+            // Cálculo da comissão do vendedor, sobre o valor dos itens
+            let commission = total_value as u128 * T::CommissionBasisPoints::get() as u128 / 10_000;
+            let commission = u64::try_from(commission).map_err(|_| Error::<T>::Overflow)?;
+
+            // Valor líquido dos itens: sem preços com imposto incluso, o imposto é cobrado à parte
+            let net = if prices_include_tax { total_value.saturating_sub(total_tax) } else { total_value };
+
+            // Sobretaxa do método de pagamento, somada ao total
+            let surcharge = Self::payment_surcharge(&payment_method, total_value)?;
+            let total_value = total_value.checked_add(surcharge).ok_or(Error::<T>::Overflow)?;
+
+            // Parcela dos consignantes, paga pelo comprador diretamente a eles e não à loja
+            let consigned_total = consignments.iter().try_fold(0u64, |total, (_, owed)| total.checked_add(*owed)).ok_or(Error::<T>::Overflow)?;
+            let store_due = total_value.saturating_sub(consigned_total);
+
+            // Parcela paga com o cartão-presente, limitada ao seu saldo e ao que é devido à loja
+            let gift_card_redeemed = gift_card_balance.map_or(0, |(_, balance)| balance.min(store_due));
+
+            // Validação da divisão do pagamento: os métodos pagam o que o cartão-presente e os repasses não cobrem
+            let due = store_due - gift_card_redeemed;
+            let payments = match split {
+                Some(payments) => {
+                    let paid = payments.iter().try_fold(0u64, |paid, (_, amount)| paid.checked_add(*amount));
+                    ensure!(paid == Some(due), Error::<T>::PaymentMismatch);
+                    payments
+                }
+                None => vec![(payment_method.clone(), due)]
+            };
+
+            // Conversão do total para a moeda de exibição
+            let display_currency = match exchange_rate {
+                Some((code, rate)) => {
+                    let converted = T::Rounding::get().divide(total_value as u128 * 10_000, rate as u128);
+                    Some((code, u64::try_from(converted).map_err(|_| Error::<T>::Overflow)?))
+                }
+                None => None
+            };
+
+            // Cobrança da taxa da venda, antes de qualquer outra escrita
+            let fee = T::SaleFee::get();
+            if !fee.is_zero() {
+                let credit = <T::Currency as fungible::Balanced<T::AccountId>>::withdraw(&who, fee, Precision::Exact, Preservation::Expendable, Fortitude::Polite)
+                    .map_err(|_| Error::<T>::InsufficientBalanceForFee)?;
+                T::FeeDestination::on_unbalanced(credit);
+                Self::deposit_event(Event::SaleFeeCharged(fee));
+            }
+
+            // Repasse do custo dos itens em consignação, pago pelo comprador a cada consignante e
+            // descontado do que é pago à loja
+            for (consignor, owed) in consignments {
+                let amount: BalanceOf<T> = owed.unique_saturated_into();
+                if amount.is_zero() {
+                    continue;
+                }
+                let credit = <T::Currency as fungible::Balanced<T::AccountId>>::withdraw(&who, amount, Precision::Exact, Preservation::Expendable, Fortitude::Polite)
+                    .map_err(|_| Error::<T>::ConsignmentPaymentFailed)?;
+                <T::Currency as fungible::Balanced<T::AccountId>>::resolve(&consignor, credit)
+                    .map_err(|_| Error::<T>::ConsignmentPaymentFailed)?;
+                SaleConsignments::<T>::insert(sale_code, &consignor, amount);
+                Self::deposit_event(Event::ConsignmentSettled(consignor, amount));
+            }
+
+            // Atualização do estoque dos produtos
+            for (id, product) in updated.iter() {
+                Self::update_restock_alert(*id, product);
+                Products::<T>::insert(id, product);
+            }
+            let sale_products: Vec<u64> = updated.into_iter().map(|(id, _)| id).collect();
+            for (category, amount) in category_stock_taken {
+                Self::decrease_category_stock(&category, amount);
+            }
+
+            // Registro das encomendas dos itens vendidos sem estoque
+            for (id, deficit) in backorders {
+                Backorders::<T>::mutate(id, |total| *total = total.saturating_add(deficit));
+                Self::deposit_event(Event::Backordered(id, deficit));
+            }
+
+            // Débito do cartão-presente
+            let gift_card = gift_card_balance.map(|(card, balance)| {
+                let remaining = balance - gift_card_redeemed;
+                GiftCards::<T>::insert(card, remaining);
+                Self::deposit_event(Event::GiftCardRedeemed(card, gift_card_redeemed, remaining));
+                (card, gift_card_redeemed)
+            });
+
+            if surcharge > 0 {
+                Self::deposit_event(Event::SurchargeApplied(payment_method.clone(), surcharge));
+            }
+            for id in floored {
+                Self::deposit_event(Event::PriceFloored(id));
+            }
+
+            let sale = Sale {
+                seller,
+                code: sale_code,
+                products: sale_products,
+                value: total_value,
+                date: date.clone(),
+                payment_method,
+                lines,
+                gift_card,
+                display_currency,
+                commission,
+                tax: total_tax,
+                net,
+                surcharge,
+                payments
+            };
+
+            // Atualização da receita do dia, sem as parcelas dos consignantes
+            let store_value = sale.store_value();
+            DailyRevenue::<T>::try_mutate(&date, |revenue| -> DispatchResult {
+                *revenue = revenue.checked_add(store_value as u128).ok_or(Error::<T>::Overflow)?;
+                Ok(())
+            })?;
+
+            // Atualização do total gasto pelo comprador
+            BuyerSpend::<T>::try_mutate(&who, |spend| -> DispatchResult {
+                *spend = spend.checked_add(total_value as u128).ok_or(Error::<T>::Overflow)?;
+                Ok(())
+            })?;
+            Self::track_sale_value(store_value)?;
+            SaleBuyers::<T>::insert(sale_code, who);
+            SaleCreatedAt::<T>::insert(sale_code, frame_system::Pallet::<T>::block_number());
+            if !T::SaleRetentionBlocks::get().is_zero() {
+                Self::enqueue_for_pruning(sale_code, frame_system::Pallet::<T>::block_number());
+            }
+
+            // Registro da venda no histórico de cada produto e na receita de cada categoria
+            let bulk_threshold = T::BulkQtyThreshold::get();
+            for line in sale.lines.iter() {
+                if bulk_threshold > 0 && line.amount >= bulk_threshold {
+                    Self::deposit_event(Event::BulkQuantitySold(line.product_id, line.amount));
+                }
+                ProductSales::<T>::mutate(line.product_id, sale_code, |amount| *amount = amount.saturating_add(line.amount));
+                CategoryRevenue::<T>::mutate(&line.category, |revenue| *revenue = revenue.saturating_add(line.store_value() as u128));
+                UnitsSold::<T>::mutate(line.product_id, |units| *units = units.saturating_add(line.amount));
+                Self::record_velocity_sample(line.product_id, line.amount);
+            }
+            let now = frame_system::Pallet::<T>::block_number();
+            for id in sale.products.iter() {
+                SaleTxCount::<T>::mutate(id, |count| *count = count.saturating_add(1));
+                LastSoldAt::<T>::insert(id, now);
+                if !FirstSold::<T>::contains_key(id) {
+                    FirstSold::<T>::insert(id, ());
+                    Self::deposit_event(Event::ProductFirstSold(*id, now));
+                }
+            }
+
+            // Inserir venda no armazenamento
+            SalesThisBlock::<T>::put(sales_this_block + 1);
+            if commission > 0 {
+                Self::deposit_event(Event::CommissionAccrued(sale.seller.clone(), commission));
+            }
+            // Tópicos para assinatura da venda por código, vendedor e produtos
+            let mut topics = vec![Self::sale_topic(sale_code), Self::seller_topic(&sale.seller)];
+            topics.extend(sale.products.iter().map(|id| Self::product_topic(*id)));
+            let breakdown = Self::category_breakdown(&sale.lines);
+            Sales::<T>::insert(sale_code, sale);
+            if let Some(next_sale_code) = next_sale_code {
+                NextSaleCode::<T>::put(next_sale_code);
+            }
+            Self::bump_catalog_version();
+            Self::bump_sales_version();
+            Self::deposit_event(Event::SaleCategoryBreakdown(sale_code, breakdown));
+            Self::deposit_event_indexed(Event::SaleRegistered(sale_code), &topics);
+
+            Ok(sale_code)
+        }
+
+        /// Incrementa a versão do catálogo após uma alteração de produtos.
+        fn bump_catalog_version() {
+            CatalogVersion::<T>::mutate(|version| *version = version.wrapping_add(1));
+        }
+
+        /// Incrementa a versão das vendas após uma alteração de vendas.
+        fn bump_sales_version() {
+            SalesVersion::<T>::mutate(|version| *version = version.wrapping_add(1));
+        }
+
+        /// Sinaliza os alertas de reposição abertos há mais de `RestockSlaBlocks` blocos, consumindo o início
+        /// de `RestockSlaQueue`: a busca termina no primeiro alerta ainda dentro do prazo, e no máximo
+        /// `MaxSlaChecksPerBlock` posições são consumidas por bloco. Alertas já encerrados apenas saem da fila.
+        /// Retorna a quantidade de leituras e escritas realizadas.
+        fn check_restock_sla(now: BlockNumberFor<T>) -> (u64, u64) {
+            let sla = T::RestockSlaBlocks::get();
+            let (mut head, tail) = RestockSlaQueueBounds::<T>::get();
+            let mut reads: u64 = 1;
+            let mut writes: u64 = 0;
+            let mut consumed: u32 = 0;
+            while head < tail && consumed < T::MaxSlaChecksPerBlock::get() {
+                reads += 1;
+                if let Some((since, _)) = RestockSlaQueue::<T>::get(head) {
+                    if now.saturating_sub(since) <= sla {
+                        break;
+                    }
+                }
+                let entry = RestockSlaQueue::<T>::take(head);
+                head += 1;
+                consumed += 1;
+                writes += 1;
+
+                let Some((since, id)) = entry else { continue };
+                // O alerta pode ter sido encerrado, ou encerrado e aberto novamente, desde a entrada na fila
+                reads += 1;
+                if LowStockSince::<T>::get(id) == Some(since) {
+                    Self::deposit_event(Event::RestockSlaBreached(id, now.saturating_sub(since)));
+                }
+            }
+
+            if consumed > 0 {
+                RestockSlaQueueBounds::<T>::put((head, tail));
+                writes += 1;
+            }
+
+            (reads, writes)
+        }
+
+        /// Inclui a venda no fim da fila de remoção das vendas antigas.
+        fn enqueue_for_pruning(code: u64, created_at: BlockNumberFor<T>) {
+            SalePruneQueueBounds::<T>::mutate(|(_, tail)| {
+                SalePruneQueue::<T>::insert(*tail, (created_at, code));
+                *tail = tail.saturating_add(1);
+            });
+        }
+
+        /// Remove, em lotes limitados, as vendas criadas há mais de `SaleRetentionBlocks` blocos.
+        /// As vendas são consumidas do início de `SalePruneQueue`, que segue a ordem de criação: a busca
+        /// termina na primeira venda ainda dentro da retenção, e no máximo `MaxSalesPrunedPerBlock` posições
+        /// da fila são consumidas por bloco. Posições de vendas já removidas apenas saem da fila.
+        /// Os totais agregados (receitas e gastos) são mantidos.
+        /// Retorna a quantidade de leituras e escritas realizadas.
+        fn prune_old_sales(now: BlockNumberFor<T>) -> (u64, u64) {
+            let retention = T::SaleRetentionBlocks::get();
+            if retention.is_zero() {
+                return (0, 0);
+            }
+
+            let (mut head, tail) = SalePruneQueueBounds::<T>::get();
+            let mut reads: u64 = 1;
+            let mut writes: u64 = 0;
+            let mut pruned: u32 = 0;
+            let mut consumed: u32 = 0;
+            while head < tail && consumed < T::MaxSalesPrunedPerBlock::get() {
+                reads += 1;
+                if let Some((created_at, _)) = SalePruneQueue::<T>::get(head) {
+                    if now.saturating_sub(created_at) <= retention {
+                        break;
+                    }
+                }
+                let entry = SalePruneQueue::<T>::take(head);
+                head += 1;
+                consumed += 1;
+                writes += 1;
+
+                let Some((created_at, code)) = entry else { continue };
+                // O código pode ter sido reutilizado por uma venda mais recente após a remoção da original
+                reads += 1;
+                if SaleCreatedAt::<T>::get(code) != Some(created_at) {
+                    continue;
+                }
+                reads += 1;
+                if let Some(sale) = Sales::<T>::take(code) {
+                    for line in sale.lines.iter() {
+                        ProductSales::<T>::remove(line.product_id, code);
+                        writes += 1;
+                    }
+                }
+                let consignments = SaleConsignments::<T>::clear_prefix(code, u32::MAX, None);
+                SaleBuyers::<T>::remove(code);
+                SaleCreatedAt::<T>::remove(code);
+                writes += 3 + consignments.unique as u64;
+                pruned += 1;
+            }
+
+            if consumed == 0 {
+                return (reads, writes);
+            }
+            SalePruneQueueBounds::<T>::put((head, tail));
+            writes += 1;
+
+            if pruned > 0 {
+                Self::bump_sales_version();
+                Self::deposit_event(Event::SalesPruned(pruned));
+                writes += 1;
+            }
+
+            (reads, writes)
+        }
+
+        /// Remove o produto e os seus índices, guardando-o para que a remoção possa ser desfeita.
+        fn do_remove_product(id: u64, product: ProductOf<T>) {
+            Self::unindex_category(&product.category, id);
+            PendingDeletions::<T>::remove(id);
+            Consignors::<T>::remove(id);
+            FlashPrices::<T>::remove(id);
+            AlertState::<T>::remove(id);
+            LowStockSince::<T>::remove(id);
+            RecentSales::<T>::remove(id);
+            PriceHistory::<T>::remove(id);
+            let _ = ProductSales::<T>::clear_prefix(id, u32::MAX, None);
+            SaleTxCount::<T>::remove(id);
+            UnitsSold::<T>::remove(id);
+            LastSoldAt::<T>::remove(id);
+            FirstSold::<T>::remove(id);
+            let owner = ProductOwners::<T>::take(id);
+            if let Some(owner) = &owner {
+                OwnerProducts::<T>::remove(owner, id);
+            }
+            ProductCreatedAt::<T>::remove(id);
+            Backorders::<T>::remove(id);
+            Self::decrease_category_stock(&product.category, product.stock);
+            Products::<T>::remove(id);
+            LastRemovedProduct::<T>::put((product, owner, frame_system::Pallet::<T>::block_number()));
+
+            // O ID é descartado caso a lista de IDs livres esteja cheia
+            if T::RecycleIds::get() {
+                let _ = FreeProductIds::<T>::try_append(id);
+            }
+            Self::bump_catalog_version();
+            Self::deposit_event(Event::ProductRemoved(id));
+        }
+
+        /// Move um produto do catálogo ativo para os produtos arquivados.
+        fn archive_product(id: u64, product: ProductOf<T>) {
+            Self::unindex_category(&product.category, id);
+            PendingDeletions::<T>::remove(id);
+            Consignors::<T>::remove(id);
+            FlashPrices::<T>::remove(id);
+            AlertState::<T>::remove(id);
+            LowStockSince::<T>::remove(id);
+            RecentSales::<T>::remove(id);
+            // O produto arquivado deixa de ter responsável, como na remoção
+            if let Some(owner) = ProductOwners::<T>::take(id) {
+                OwnerProducts::<T>::remove(&owner, id);
+            }
+            Self::decrease_category_stock(&product.category, product.stock);
+            Products::<T>::remove(id);
+            ArchivedProducts::<T>::insert(id, product);
+        }
+
+        /// Calcula a quantidade de registros de uma listagem a partir do limite informado:
+        /// zero usa `DefaultPageSize` e valores acima de `MaxPageSize` são reduzidos a ele.
+        fn page_size(limit: u32) -> usize {
+            let limit = if limit == 0 { T::DefaultPageSize::get() } else { limit };
+            limit.min(T::MaxPageSize::get()) as usize
+        }
+
+        /// Indica se o estoque do produto chegou ao ponto de reposição.
+        /// Usada pelas listagens, pelas estatísticas e pelos alertas de reposição.
+        fn needs_reorder(id: u64, product: &ProductOf<T>) -> bool {
+            product.stock <= Self::effective_restock_threshold(id, product)
+        }
+
+        /// Indica se o estoque superou o nível de encerramento do alerta de reposição, que por padrão
+        /// é o próprio ponto de reposição.
+        fn restock_recovered(id: u64, product: &ProductOf<T>) -> bool {
+            let clear_level = product.restock_clear_level.unwrap_or_else(|| Self::effective_restock_threshold(id, product));
+            product.stock > clear_level
+        }
+
+        /// Calcula o ponto de reposição do produto em unidades. No modo `Units`, é o `reorder_point`; no modo
+        /// `DaysOfCover`, é a quantidade vendida em `reorder_point` dias, conforme a velocidade média de vendas desde a criação.
+        pub fn effective_restock_threshold(id: u64, product: &ProductOf<T>) -> u64 {
+            match product.threshold_mode {
+                ThresholdMode::Units => product.reorder_point,
+                ThresholdMode::DaysOfCover => {
+                    let now = frame_system::Pallet::<T>::block_number();
+                    let created_at = Self::product_created_at(id).unwrap_or(now);
+                    let elapsed: u128 = now.saturating_sub(created_at).unique_saturated_into();
+                    let blocks_per_day: u128 = T::BlocksPerDay::get().unique_saturated_into();
+
+                    let units = product.reorder_point as u128 * Self::units_sold(id) as u128 * blocks_per_day;
+                    let threshold = RoundingMode::Up.divide(units, elapsed.max(1));
+                    u64::try_from(threshold).unwrap_or(u64::MAX)
+                }
+            }
+        }
+
+        /// Emite um evento com tópicos, permitindo que clientes leves filtrem os eventos de interesse.
+        fn deposit_event_indexed(event: Event<T>, topics: &[T::Hash]) {
+            let event = <T as Config>::RuntimeEvent::from(event);
+            frame_system::Pallet::<T>::deposit_event_indexed(topics, event.into());
+        }
+
+        /// Tópico dos eventos relacionados a um produto.
+        pub fn product_topic(id: u64) -> T::Hash {
+            T::Hashing::hash_of(&(b"product", id))
+        }
+
+        /// Tópico dos eventos relacionados a uma venda.
+        pub fn sale_topic(code: u64) -> T::Hash {
+            T::Hashing::hash_of(&(b"sale", code))
+        }
+
+        /// Tópico dos eventos relacionados a um vendedor.
+        pub fn seller_topic(seller: &[u8]) -> T::Hash {
+            T::Hashing::hash_of(&(b"seller", seller))
+        }
+
+        /// Verifica se a categoria comporta `added` unidades a mais sem ultrapassar seu limite de estoque.
+        fn ensure_category_capacity(category: &Category, added: u64) -> Result<(), Error<T>> {
+            if let Some(cap) = CategoryStockCaps::<T>::get(category) {
+                let total = Self::category_stock_total(category).saturating_add(added);
+                ensure!(total <= cap, Error::<T>::CategoryCapExceeded);
+            }
+            Ok(())
+        }
+
+        /// Soma `amount` ao estoque total da categoria.
+        fn increase_category_stock(category: &Category, amount: u64) {
+            CategoryStockTotal::<T>::mutate(category, |total| *total = total.saturating_add(amount));
+        }
+
+        /// Subtrai `amount` do estoque total da categoria.
+        fn decrease_category_stock(category: &Category, amount: u64) {
+            CategoryStockTotal::<T>::mutate(category, |total| *total = total.saturating_sub(amount));
+        }
+
+        /// Inclui o produto no índice da categoria, contando-o uma única vez.
+        fn index_category(category: &Category, id: u64) {
+            if !ProductsByCategory::<T>::contains_key(category, id) {
+                ProductsByCategory::<T>::insert(category, id, ());
+                CategoryCounts::<T>::mutate(category, |count| *count = count.saturating_add(1));
+            }
+        }
+
+        /// Retira o produto do índice da categoria, se estiver nele.
+        fn unindex_category(category: &Category, id: u64) {
+            if ProductsByCategory::<T>::take(category, id).is_some() {
+                CategoryCounts::<T>::mutate_exists(category, |count| {
+                    *count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0);
+                });
+            }
+        }
+
+        /// Registra as unidades vendidas no bloco atual, descartando as amostras fora de `VelocityWindow`.
+        /// Vendas do mesmo bloco são somadas na mesma amostra; sem espaço, a amostra mais antiga é descartada.
+        fn record_velocity_sample(id: u64, amount: u64) {
+            let now = frame_system::Pallet::<T>::block_number();
+            let window = T::VelocityWindow::get();
+            RecentSales::<T>::mutate(id, |samples| {
+                samples.retain(|(block, _)| now.saturating_sub(*block) <= window);
+                match samples.iter_mut().last() {
+                    Some((block, units)) if *block == now => *units = units.saturating_add(amount),
+                    _ => {
+                        if samples.is_full() {
+                            samples.remove(0);
+                        }
+                        let _ = samples.try_push((now, amount));
+                    }
+                }
+            });
+        }
+
+        /// Contabiliza uma venda no valor médio das vendas.
+        fn track_sale_value(value: u64) -> Result<(), Error<T>> {
+            TotalSalesValue::<T>::try_mutate(|total| -> Result<(), Error<T>> {
+                *total = total.checked_add(value as u128).ok_or(Error::<T>::Overflow)?;
+                Ok(())
+            })?;
+            SaleCount::<T>::mutate(|count| *count = count.saturating_add(1));
+            Ok(())
+        }
+
+        /// Retira uma venda estornada do valor médio das vendas.
+        fn untrack_sale_value(value: u64) {
+            TotalSalesValue::<T>::mutate(|total| *total = total.saturating_sub(value as u128));
+            SaleCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+        }
+
+        /// Converte os atributos informados, respeitando `MaxAttributes` e os tamanhos de chave e valor.
+        fn bound_attributes(attributes: Vec<(Vec<u8>, Vec<u8>)>) -> Result<Attributes<T::MaxAttributes>, Error<T>> {
+            ensure!(attributes.len() as u32 <= T::MaxAttributes::get(), Error::<T>::AttributeLimitExceeded);
+
+            let attributes = attributes
+                .into_iter()
+                .map(|(key, value)| Ok((key.try_into().map_err(|_| Error::<T>::AttributeLimitExceeded)?, value.try_into().map_err(|_| Error::<T>::AttributeLimitExceeded)?)))
+                .collect::<Result<Vec<_>, Error<T>>>()?;
+            attributes.try_into().map_err(|_| Error::<T>::AttributeLimitExceeded)
+        }
+
+        /// Indica se o produto tem o estoque mínimo (`MinListableStock`) para ser listado e vendido.
+        fn is_listable(product: &ProductOf<T>) -> bool {
+            product.stock >= T::MinListableStock::get()
+        }
+
+        /// Retorna erro caso o produto esteja travado por uma operação administrativa.
+        fn ensure_not_locked(id: u64) -> Result<(), Error<T>> {
+            ensure!(!LockedProducts::<T>::contains_key(id), Error::<T>::ProductLocked);
+            Ok(())
+        }
+
+        /// Retorna erro caso o pallet esteja pausado para manutenção.
+        fn ensure_not_paused() -> Result<(), Error<T>> {
+            ensure!(!Self::paused(), Error::<T>::Paused);
+            Ok(())
+        }
+
+        /// Valida uma data informada, exigindo que o ano esteja entre `MinYear` e `MaxYear`.
+        fn validate_date(date: Date) -> Result<Date, Error<T>> {
+            let date = Date::new(date.day, date.month, date.year).map_err(|_| Error::<T>::InvalidDate)?;
+            ensure!((T::MinYear::get()..=T::MaxYear::get()).contains(&date.year), Error::<T>::InvalidDate);
+            Ok(date)
+        }
+
+        /// Arredonda um preço calculado para um múltiplo de `PriceIncrement`, conforme o modo de arredondamento.
+        /// Um incremento de 1 (ou 0) não altera o valor.
+        pub fn round_to_increment(value: u64) -> u64 {
+            let increment = T::PriceIncrement::get();
+            if increment <= 1 {
+                return value;
+            }
+
+            let steps = T::Rounding::get().divide(value as u128, increment as u128);
+            u64::try_from(steps * increment as u128).unwrap_or(u64::MAX)
+        }
+
+        /// Retorna a data atual conforme o horário on-chain.
+        pub fn today() -> Date {
+            Date::from_unix_secs(T::TimeProvider::now().as_secs())
+        }
+
+        /// Rejeita datas de reposição anteriores à data atual, quando `EnforceFutureRestock` está ativo.
+        fn ensure_restock_date_not_past(restock_date: &Date) -> Result<(), Error<T>> {
+            if T::EnforceFutureRestock::get() {
+                ensure!(*restock_date >= Self::today(), Error::<T>::RestockDateInPast);
+            }
+            Ok(())
+        }
+
+        /// Soma o valor dos itens por categoria, na ordem da enumeração e omitindo categorias sem itens.
+        /// O resultado tem no máximo uma entrada por categoria.
+        fn category_breakdown(lines: &[SaleLine]) -> Vec<(Category, u64)> {
+            Category::ALL
+                .into_iter()
+                .filter_map(|category| {
+                    let in_category: Vec<&SaleLine> = lines.iter().filter(|line| line.category == category).collect();
+                    if in_category.is_empty() {
+                        return None;
+                    }
+                    let subtotal = in_category.iter().fold(0u64, |total, line| total.saturating_add(line.value));
+                    Some((category, subtotal))
+                })
+                .collect()
+        }
+
+        /// Calcula o imposto de um item de venda conforme a alíquota da categoria tributária.
+        /// Com preços que já incluem o imposto, ele é extraído do valor: valor - valor / (1 + alíquota).
+        fn line_tax(value: u64, tax_category: u8, prices_include_tax: bool) -> Result<u64, Error<T>> {
+            let tax_rate = TaxRates::<T>::get(tax_category).unwrap_or_else(T::DefaultTaxRate::get);
+            let tax = if prices_include_tax {
+                let net = T::Rounding::get().divide(value as u128 * 10_000, 10_000 + tax_rate as u128);
+                (value as u128).saturating_sub(net)
+            } else {
+                T::Rounding::get().divide(value as u128 * tax_rate as u128, 10_000)
+            };
+            u64::try_from(tax).map_err(|_| Error::<T>::Overflow)
+        }
+
+        /// Calcula o imposto de um item sem arredondamento, na escala de 1/10.000.
+        fn scaled_line_tax(value: u64, tax_category: u8, prices_include_tax: bool) -> u128 {
+            let tax_rate = TaxRates::<T>::get(tax_category).unwrap_or_else(T::DefaultTaxRate::get) as u128;
+            if prices_include_tax {
+                value as u128 * tax_rate * 10_000 / (10_000 + tax_rate)
+            } else {
+                value as u128 * tax_rate
+            }
+        }
+
+        /// Arredonda o imposto total da venda a partir de `scaled_tax` e o distribui entre os itens
+        /// proporcionalmente ao valor de cada um. O resíduo do arredondamento fica com o item de maior
+        /// valor, de modo que o imposto dos itens soma exatamente o total retornado.
+        fn allocate_sale_tax(lines: &mut [SaleLine], scaled_tax: u128) -> u64 {
+            let total_tax = u64::try_from(T::Rounding::get().divide(scaled_tax, 10_000)).unwrap_or(u64::MAX);
+            let items_value = lines.iter().fold(0u128, |total, line| total + line.value as u128);
+            if items_value == 0 {
+                lines.iter_mut().for_each(|line| line.tax = 0);
+                return 0;
+            }
+
+            let mut allocated: u64 = 0;
+            for line in lines.iter_mut() {
+                // Parcela arredondada para baixo, nunca maior que o total
+                line.tax = (total_tax as u128 * line.value as u128 / items_value) as u64;
+                allocated = allocated.saturating_add(line.tax);
+            }
+            if let Some(largest) = lines.iter_mut().max_by_key(|line| line.value) {
+                largest.tax = largest.tax.saturating_add(total_tax.saturating_sub(allocated));
+            }
+
+            total_tax
+        }
+
+        /// Calcula a sobretaxa do método de pagamento sobre o valor dos itens.
+        fn payment_surcharge(payment_method: &PaymentMethod, value: u64) -> Result<u64, Error<T>> {
+            match PaymentSurcharges::<T>::get(payment_method) {
+                Some(rate) => {
+                    let surcharge = T::Rounding::get().divide(value as u128 * rate as u128, 10_000);
+                    u64::try_from(surcharge).map_err(|_| Error::<T>::Overflow)
+                }
+                None => Ok(0)
+            }
+        }
+
+        /// Calcula o valor de um item de venda, aplicando o desconto percentual quando informado.
+        /// Os cálculos intermediários são feitos em u128; apenas o valor final precisa caber em u64.
+        fn line_value(price: u64, amount: u64, discount_percent: Option<u8>) -> Result<u64, Error<T>> {
+            // A quantidade está na escala de `StockUnit`, enquanto o preço se refere a uma unidade inteira
+            let value = T::Rounding::get().divide(price as u128 * amount as u128, T::StockUnit::get().max(1) as u128);
+
+            match discount_percent {
+                Some(percent) => {
+                    ensure!(percent <= 100, Error::<T>::InvalidDiscount);
+                    let discounted = value * (100 - percent as u128) / 100;
+                    let discounted = u64::try_from(discounted).map_err(|_| Error::<T>::Overflow)?;
+                    Ok(Self::round_to_increment(discounted))
+                }
+                None => u64::try_from(value).map_err(|_| Error::<T>::Overflow)
+            }
+        }
+
+        /// Calcula o valor do item com o desconto informado, sem ficar abaixo do piso de preço,
+        /// e indica se o piso foi aplicado.
+        fn floored_line_value(unit_price: u64, amount: u64, discount_percent: Option<u8>, price_floor: Option<u64>) -> Result<(u64, bool), Error<T>> {
+            let value = Self::line_value(unit_price, amount, discount_percent)?;
+            let floor_value = match price_floor {
+                Some(floor) => Self::line_value(floor, amount, None)?,
+                None => 0
+            };
+            Ok((value.max(floor_value), value < floor_value))
+        }
+
+        /// Registra um novo preço no histórico do produto, descartando os registros mais antigos
+        /// que `PriceHistoryMaxAge` e, se o histórico estiver cheio, o registro mais antigo.
+        fn record_price(id: u64, price: u64) {
+            let now = frame_system::Pallet::<T>::block_number();
+            let max_age = T::PriceHistoryMaxAge::get();
+
+            PriceHistory::<T>::mutate(id, |history| {
+                history.retain(|(at, _)| now.saturating_sub(*at) <= max_age);
+
+                if history.is_full() {
+                    history.remove(0);
+                }
+
+                let _ = history.try_push((now, price));
+            });
+        }
+
+        /// Atualiza o alerta de reposição do produto com histerese: o alerta é emitido quando o estoque
+        /// chega ao ponto de reposição e só pode ser emitido novamente depois que o estoque supera o nível de encerramento.
+        fn update_restock_alert(id: u64, product: &ProductOf<T>) {
+            let alerted = AlertState::<T>::get(id);
+
+            if !alerted && Self::needs_reorder(id, product) {
+                let now = frame_system::Pallet::<T>::block_number();
+                AlertState::<T>::insert(id, true);
+                LowStockSince::<T>::insert(id, now);
+                RestockSlaQueueBounds::<T>::mutate(|(_, tail)| {
+                    RestockSlaQueue::<T>::insert(*tail, (now, id));
+                    *tail = tail.saturating_add(1);
+                });
+                Self::deposit_event(Event::ProductNeedsRestock(id));
+            } else if alerted && Self::restock_recovered(id, product) {
+                AlertState::<T>::remove(id);
+                LowStockSince::<T>::remove(id);
+                Self::deposit_event(Event::RestockAlertCleared(id));
+            }
+        }
+
+        /// Verifica se o produto existe, sem decodificá-lo.
+        pub fn product_exists(id: u64) -> bool {
+            Products::<T>::contains_key(id)
+        }
+
+        /// Recalcula o valor total de uma venda com os preços atuais dos produtos, incluindo os
+        /// arquivados, e as quantidades registradas, sem descontos. Itens de produtos removidos mantêm
+        /// o valor registrado. Como o valor registrado, o resultado inclui a sobretaxa vigente do
+        /// método de pagamento da venda.
+        pub fn revalue_sale(code: u64) -> Option<u64> {
+            let sale = Sales::<T>::get(code)?;
+
+            let items_value = sale.lines.iter().fold(0u64, |total, line| {
+                let product = Products::<T>::get(line.product_id).or_else(|| ArchivedProducts::<T>::get(line.product_id));
+                let line_value = match product {
+                    Some(product) => Self::line_value(product.price, line.amount, None).unwrap_or(u64::MAX),
+                    None => line.value
+                };
+                total.saturating_add(line_value)
+            });
+            let surcharge = Self::payment_surcharge(&sale.payment_method, items_value).unwrap_or(u64::MAX);
+
+            Some(items_value.saturating_add(surcharge))
+        }
+
+        /// Reconstrói o recibo de uma venda a partir dos itens registrados, com o nome atual dos produtos,
+        /// incluindo os arquivados. O preço unitário e o desconto vêm dos preços registrados na venda, de
+        /// modo que mudanças de preço posteriores não alteram o recibo.
+        pub fn receipt(code: u64) -> Option<Receipt> {
+            let sale = Sales::<T>::get(code)?;
+
+            let lines: Vec<ReceiptLine> = sale.lines.iter().map(|line| {
+                let product = Products::<T>::get(line.product_id).or_else(|| ArchivedProducts::<T>::get(line.product_id));
+                let gross = Self::line_value(line.unit_price, line.amount, None).unwrap_or(line.value);
+                ReceiptLine {
+                    product_id: line.product_id,
+                    name: product.map(|product| product.name).unwrap_or_default(),
+                    unit_price: line.unit_price,
+                    amount: line.amount,
+                    discount: gross.saturating_sub(line.value),
+                    line_total: line.value,
+                    tax: line.tax
+                }
+            }).collect();
+            let subtotal = lines.iter().fold(0u64, |total, line| total.saturating_add(line.line_total));
+
+            Some(Receipt {
+                code,
+                lines,
+                subtotal,
+                tax: sale.tax,
+                surcharge: sale.surcharge,
+                total: sale.value
+            })
+        }
+
+        /// Retorna o tamanho, em bytes, da codificação SCALE do produto armazenado.
+        pub fn product_encoded_size(id: u64) -> Option<u32> {
+            Products::<T>::get(id).map(|product| product.encoded_size() as u32)
+        }
+
+        /// Verifica se a venda existe, sem decodificá-la.
+        pub fn sale_exists(code: u64) -> bool {
+            Sales::<T>::contains_key(code)
+        }
+
+        /// Verifica se a quantidade informada do produto pode ser vendida, com as mesmas validações
+        /// aplicadas a cada item de uma venda. Usada pela API de runtime para que interfaces
+        /// habilitem a compra com uma única consulta.
+        pub fn is_sellable(id: u64, amount: u64) -> bool {
+            let item = ItemSale { product_id: id, amount, discount_percent: None };
+            Self::check_cart(vec![item], false).is_ok()
+        }
+
+        /// Retorna, em ordem crescente, os códigos das vendas criadas entre os blocos `from` e `to` (inclusive),
+        /// limitados a `MaxPageSize` resultados.
+        pub fn sales_in_block_range(from: BlockNumberFor<T>, to: BlockNumberFor<T>) -> Vec<u64> {
+            let mut codes: Vec<u64> = SaleCreatedAt::<T>::iter()
+                .filter(|(_, created_at)| *created_at >= from && *created_at <= to)
+                .map(|(code, _)| code)
+                .collect();
+
+            codes.sort();
+            codes.truncate(T::MaxPageSize::get() as usize);
+            codes
+        }
+
+        /// Retorna o ID e o preço do produto mais barato da categoria, desempatando pelo menor ID.
+        /// Quando `in_stock_only` é verdadeiro, produtos sem estoque são ignorados.
+        pub fn cheapest_in_category(category: Category, in_stock_only: bool) -> Option<(u64, u64)> {
+            ProductsByCategory::<T>::iter_key_prefix(&category)
+                .filter_map(|id| Products::<T>::get(id).map(|product| (id, product)))
+                .filter(|(_, product)| Self::is_listable(product) && (!in_stock_only || product.stock > 0))
+                .map(|(id, product)| (id, product.price))
+                .min_by_key(|(id, price)| (*price, *id))
+        }
+
+        /// Retorna uma página de produtos a partir do cursor informado (ou do início, se ausente),
+        /// com o cursor da próxima página quando ainda houver produtos.
+        pub fn products_page(cursor: Option<StorageCursor>, limit: u32) -> (Vec<(u64, ProductOf<T>)>, Option<StorageCursor>) {
+            let mut iter = match cursor {
+                Some(StorageCursor(raw_key)) => Products::<T>::iter_from(raw_key),
+                None => Products::<T>::iter()
+            };
+
+            let page: Vec<(u64, ProductOf<T>)> = iter.by_ref().take(Self::page_size(limit)).collect();
+            let next = match (page.last(), iter.next()) {
+                (Some((id, _)), Some(_)) => Some(StorageCursor(Products::<T>::hashed_key_for(id))),
+                _ => None
+            };
+
+            (page, next)
+        }
+
+        /// Retorna o ID e o valor em estoque do produto de maior valor em estoque, com o estoque convertido
+        /// de `StockUnit` em unidades inteiras, desempatando pelo menor ID. Produtos cujo valor estoura são ignorados.
+        pub fn highest_value_product() -> Option<(u64, u64)> {
+            Products::<T>::iter()
+                .filter_map(|(id, product)| Self::line_value(product.price, product.stock, None).ok().map(|value| (id, value)))
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+        }
+
+        /// Retorna o valor médio das vendas, ou zero se não houver vendas.
+        pub fn average_sale_value() -> u64 {
+            // A média nunca supera o valor de uma venda, que cabe em u64
+            Self::total_sales_value().checked_div(Self::sale_count() as u128).unwrap_or(0).unique_saturated_into()
+        }
+
+        /// Retorna a unidade em que o estoque do produto é contado, se o produto existir.
+        pub fn stock_unit_label(id: u64) -> Option<StockUnitLabel> {
+            Products::<T>::get(id).map(|product| product.unit)
+        }
+
+        /// Retorna o preço relâmpago do produto, se houver um ainda não expirado.
+        pub fn flash_price(id: u64) -> Option<u64> {
+            FlashPrices::<T>::get(id)
+                .filter(|(_, until)| *until >= frame_system::Pallet::<T>::block_number())
+                .map(|(price, _)| price)
+        }
+
+        /// Retorna as unidades do produto vendidas nos últimos `VelocityWindow` blocos.
+        pub fn sales_velocity(id: u64) -> u64 {
+            let now = frame_system::Pallet::<T>::block_number();
+            let window = T::VelocityWindow::get();
+            RecentSales::<T>::get(id)
+                .iter()
+                .filter(|(block, _)| now.saturating_sub(*block) <= window)
+                .fold(0u64, |total, (_, units)| total.saturating_add(*units))
+        }
+
+        /// Retorna as categorias com pelo menos um produto ativo, na ordem da enumeração.
+        pub fn used_categories() -> Vec<Category> {
+            Category::ALL
+                .into_iter()
+                .filter(|category| CategoryCounts::<T>::get(category) > 0)
+                .collect()
+        }
+
+        /// Retorna as estatísticas agregadas da loja.
+        /// As contagens vêm dos contadores dos mapas e o total de unidades, do estoque mantido por categoria;
+        /// o valor em estoque e os produtos a repor exigem percorrer o catálogo.
+        pub fn store_stats() -> StoreStats {
+            let mut stats = StoreStats {
+                product_count: Products::<T>::count(),
+                sale_count: Sales::<T>::count(),
+                total_units: CategoryStockTotal::<T>::iter_values().fold(0u64, |total, stock| total.saturating_add(stock)),
+                ..Default::default()
+            };
+
+            for (id, product) in Products::<T>::iter() {
+                let stock_value = Self::line_value(product.price, product.stock, None).unwrap_or(u64::MAX);
+                stats.total_inventory_value = stats.total_inventory_value.saturating_add(stock_value);
+                if Self::needs_reorder(id, &product) {
+                    stats.restock_needed = stats.restock_needed.saturating_add(1);
+                }
+            }
+
+            stats
+        }
+
+        /// Exporta os produtos e as vendas, ordenados por ID e código,
+        /// limitados a `MaxSnapshotSize` registros de cada tipo.
+        pub fn export_snapshot() -> (Vec<ProductOf<T>>, Vec<Sale>) {
+            let max = T::MaxSnapshotSize::get() as usize;
+
+            let mut products: Vec<(u64, ProductOf<T>)> = Products::<T>::iter().collect();
+            products.sort_by_key(|(id, _)| *id);
+            products.truncate(max);
+
+            let mut sales: Vec<(u64, Sale)> = Sales::<T>::iter().collect();
+            sales.sort_by_key(|(code, _)| *code);
+            sales.truncate(max);
+
+            (
+                products.into_iter().map(|(_, product)| product).collect(),
+                sales.into_iter().map(|(_, sale)| sale).collect()
+            )
+        }
     }
-}
\ No newline at end of file
+}
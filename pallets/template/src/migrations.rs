@@ -0,0 +1,132 @@
+//! Migrações do armazenamento do pallet.
+
+use crate::pallet::*;
+use frame_support::{
+    migrations::VersionedMigration,
+    pallet_prelude::*,
+    traits::UncheckedOnRuntimeUpgrade
+};
+use sp_std::{marker::PhantomData, vec, vec::Vec};
+
+/// Formatos dos produtos e das vendas antes da versão 1 do armazenamento.
+pub mod v0 {
+    use super::*;
+
+    /// Produto como era armazenado antes da versão 1.
+    #[derive(Clone, Encode, Decode, Debug, PartialEq, TypeInfo)]
+    pub struct OldProduct {
+        pub name: Vec<u8>,
+        pub id: u64,
+        pub stock: u64,
+        pub price: u64,
+        pub amount_to_restock: u64,
+        pub restock_date: Date,
+        pub category: Category
+    }
+
+    /// Venda como era armazenada antes da versão 1.
+    #[derive(Clone, Encode, Decode, Debug, PartialEq, TypeInfo)]
+    pub struct OldSale {
+        pub seller: Vec<u8>,
+        pub code: u64,
+        pub products: Vec<u64>,
+        pub value: u64,
+        pub date: Date,
+        pub payment_method: PaymentMethod
+    }
+}
+
+/// Converte os produtos e as vendas da versão 0 para o formato atual, com os valores padrão nos
+/// campos novos, e reconstrói a partir deles os contadores dos mapas, os índices e o estoque das
+/// categorias e os totais de receita. Vendas antigas não têm itens detalhados nem bloco de criação,
+/// então não são corrigidas, revalorizadas nem removidas por antiguidade.
+pub struct InnerMigrateV0ToV1<T>(PhantomData<T>);
+
+impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+    fn on_runtime_upgrade() -> Weight {
+        let mut products: u64 = 0;
+        Products::<T>::translate::<v0::OldProduct, _>(|id, old| {
+            products += 1;
+            ProductsByCategory::<T>::insert(&old.category, id, ());
+            CategoryCounts::<T>::mutate(&old.category, |count| *count = count.saturating_add(1));
+            CategoryStockTotal::<T>::mutate(&old.category, |total| *total = total.saturating_add(old.stock));
+
+            Some(Product {
+                name: old.name,
+                id: old.id,
+                stock: old.stock,
+                price: old.price,
+                amount_to_restock: old.amount_to_restock,
+                reorder_point: old.amount_to_restock,
+                restock_date: old.restock_date,
+                category: old.category,
+                metadata_hash: None,
+                restock_clear_level: None,
+                tax_category: 0,
+                threshold_mode: ThresholdMode::Units,
+                min_order_qty: None,
+                max_order_qty: None,
+                member_price: None,
+                promo: None,
+                attributes: Default::default(),
+                cost_price: None,
+                unit: StockUnitLabel::Piece,
+                price_floor: None
+            })
+        });
+        Products::<T>::initialize_counter();
+
+        let mut sales: u64 = 0;
+        Sales::<T>::translate::<v0::OldSale, _>(|_, old| {
+            sales += 1;
+            DailyRevenue::<T>::mutate(&old.date, |revenue| *revenue = revenue.saturating_add(old.value as u128));
+            TotalSalesValue::<T>::mutate(|total| *total = total.saturating_add(old.value as u128));
+            SaleCount::<T>::mutate(|count| *count = count.saturating_add(1));
+
+            Some(Sale {
+                seller: old.seller,
+                code: old.code,
+                products: old.products,
+                value: old.value,
+                date: old.date,
+                payment_method: old.payment_method.clone(),
+                lines: Vec::new(),
+                gift_card: None,
+                display_currency: None,
+                commission: 0,
+                tax: 0,
+                net: old.value,
+                surcharge: 0,
+                payments: vec![(old.payment_method, old.value)]
+            })
+        });
+        Sales::<T>::initialize_counter();
+
+        // Cada registro é lido e regravado com três índices ou totais; os contadores são lidos e gravados
+        T::DbWeight::get().reads_writes(products + sales + 2, 4 * (products + sales) + 2)
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, frame_support::sp_runtime::TryRuntimeError> {
+        let products = Products::<T>::iter_keys().count() as u32;
+        let sales = Sales::<T>::iter_keys().count() as u32;
+        Ok((products, sales).encode())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(state: Vec<u8>) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+        let (products, sales) = <(u32, u32)>::decode(&mut &state[..]).map_err(|_| "estado inválido")?;
+        ensure!(Products::<T>::count() == products, "produtos perdidos na migração");
+        ensure!(Sales::<T>::count() == sales, "vendas perdidas na migração");
+        Ok(())
+    }
+}
+
+/// Migração da versão 0 para a 1, executada apenas enquanto o armazenamento estiver na versão 0.
+pub type MigrateV0ToV1<T> = VersionedMigration<
+    0,
+    1,
+    InnerMigrateV0ToV1<T>,
+    Pallet<T>,
+    <T as frame_system::Config>::DbWeight
+>;
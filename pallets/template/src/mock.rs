@@ -1,5 +1,5 @@
 use crate as pallet_template;
-use frame_support::derive_impl;
+use frame_support::{derive_impl, parameter_types, traits::{ConstU32, ConstU64, Hooks, SortedMembers, UnixTime}, weights::RuntimeDbWeight};
 use sp_runtime::BuildStorage;
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -9,6 +9,7 @@ frame_support::construct_runtime!(
 	pub enum Test
 	{
 		System: frame_system,
+		Balances: pallet_balances,
 		TemplateModule: pallet_template,
 	}
 );
@@ -16,14 +17,126 @@ frame_support::construct_runtime!(
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
 impl frame_system::Config for Test {
 	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type DbWeight = DbWeight;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+parameter_types! {
+	pub static EmitListingPayloads: bool = true;
+	pub static RecycleIds: bool = false;
+	pub static MaxSalesPerBlock: u32 = 100;
+	pub static Rounding: pallet_template::RoundingMode = pallet_template::RoundingMode::Nearest;
+	pub static CommissionBasisPoints: u32 = 0;
+	pub static AllowOversell: bool = false;
+	pub static HashedSaleCodes: bool = false;
+	pub static PriceIncrement: u64 = 1;
+	pub static SaleFee: u64 = 0;
+	pub static StockUnit: u64 = 1;
+	pub static EnforceFutureRestock: bool = false;
+	pub static SaleRetentionBlocks: u64 = 0;
+	pub static BulkQtyThreshold: u64 = 0;
+	pub static IdStep: u64 = 1;
+	pub static MinListableStock: u64 = 0;
+	pub static RejectEmptyUpdates: bool = false;
+	pub static MinMarginBasisPoints: u32 = 0;
+	pub static RefundWindowBlocks: u64 = 0;
+	pub static DeletionApprovalThreshold: u64 = 0;
+	pub static TaxOnSaleTotal: bool = false;
+	// Pesos de leitura e escrita no armazenamento, nulos por padrão
+	pub static DbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 0, write: 0 };
+	// Horário on-chain, em segundos desde 01/01/1970
+	pub static Now: u64 = 0;
+}
+
+pub struct MockTime;
+
+impl UnixTime for MockTime {
+	fn now() -> core::time::Duration {
+		core::time::Duration::from_secs(Now::get())
+	}
+}
+
+// Contas 1 e 3 são funcionárias; as demais são apenas clientes.
+pub struct StaffMembers;
+
+impl SortedMembers<u64> for StaffMembers {
+	fn sorted_members() -> Vec<u64> {
+		vec![1, 3]
+	}
 }
 
 impl pallet_template::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
+	type MaxRestockAmount = ConstU64<1_000>;
+	type EmitListingPayloads = EmitListingPayloads;
+	type AdminOrigin = frame_system::EnsureRoot<u64>;
+	type StaffOrigin = frame_system::EnsureSignedBy<StaffMembers, u64>;
+	type CustomerOrigin = frame_system::EnsureSigned<u64>;
+	type MaxPriceHistory = ConstU32<3>;
+	type PriceHistoryMaxAge = ConstU64<10>;
+	type MaxPageSize = ConstU32<10>;
+	type RecycleIds = RecycleIds;
+	type MaxFreeProductIds = ConstU32<10>;
+	type MaxSalesPerBlock = MaxSalesPerBlock;
+	type Rounding = Rounding;
+	type MaxBundleSize = ConstU32<5>;
+	type CommissionBasisPoints = CommissionBasisPoints;
+	type MinYear = ConstU64<2000>;
+	type MaxYear = ConstU64<2100>;
+	type MaxSnapshotSize = ConstU32<3>;
+	type AllowOversell = AllowOversell;
+	type DefaultTaxRate = ConstU32<1_000>;
+	type HashedSaleCodes = HashedSaleCodes;
+	type RestockSlaBlocks = ConstU64<5>;
+	type MaxSlaChecksPerBlock = ConstU32<2>;
+	type DefaultPageSize = ConstU32<3>;
+	type BlocksPerDay = ConstU64<10>;
+	type PriceIncrement = PriceIncrement;
+	type Currency = Balances;
+	type SaleFee = SaleFee;
+	type FeeDestination = ();
+	type StockUnit = StockUnit;
+	type TimeProvider = MockTime;
+	type EnforceFutureRestock = EnforceFutureRestock;
+	type SaleRetentionBlocks = SaleRetentionBlocks;
+	type MaxSalesPrunedPerBlock = ConstU32<2>;
+	type BulkQtyThreshold = BulkQtyThreshold;
+	type IdStep = IdStep;
+	type UndoWindow = ConstU64<5>;
+	type SaleEditWindow = ConstU64<10>;
+	type RejectEmptyUpdates = RejectEmptyUpdates;
+	type MinMarginBasisPoints = MinMarginBasisPoints;
+	type VelocityWindow = ConstU64<10>;
+	type MaxVelocitySamples = ConstU32<3>;
+	type RefundWindowBlocks = RefundWindowBlocks;
+	type DeletionApprovalThreshold = DeletionApprovalThreshold;
+	type TaxOnSaleTotal = TaxOnSaleTotal;
+	type MinListableStock = MinListableStock;
+	type MaxAttributes = ConstU32<2>;
+	type MaxFlashExpiries = ConstU32<2>;
+	type MaxRecentSalesScan = ConstU32<8>;
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	frame_system::GenesisConfig::<Test>::default().build_storage().unwrap().into()
-}
\ No newline at end of file
+	let mut ext: sp_io::TestExternalities =
+		frame_system::GenesisConfig::<Test>::default().build_storage().unwrap().into();
+	// Eventos só são registrados a partir do bloco 1.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+// Avança até o bloco informado, executando a inicialização do pallet em cada bloco.
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		let next = System::block_number() + 1;
+		System::set_block_number(next);
+		TemplateModule::on_initialize(next);
+	}
+}
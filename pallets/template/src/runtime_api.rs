@@ -0,0 +1,57 @@
+//! API de runtime do pallet, usada por clientes para consultas somente leitura
+//! sem a necessidade de enviar extrínsecos.
+
+use crate::{Category, Receipt, Sale, StorageCursor, StoreStats};
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// Consultas sobre o catálogo e as vendas da loja.
+    /// `Product` é o tipo de produto do runtime, com os atributos limitados por `MaxAttributes`.
+    pub trait StoreApi<BlockNumber, Product> where BlockNumber: Codec, Product: Codec {
+        /// Retorna verdadeiro se o produto existe e pode ser vendido na quantidade informada.
+        fn is_sellable(id: u64, amount: u64) -> bool;
+
+        /// Retorna o ID e o preço do produto mais barato da categoria, se houver.
+        fn cheapest_in_category(category: Category, in_stock_only: bool) -> Option<(u64, u64)>;
+
+        /// Retorna os códigos das vendas criadas no intervalo de blocos informado (inclusive).
+        fn sales_in_block_range(from: BlockNumber, to: BlockNumber) -> Vec<u64>;
+
+        /// Retorna o ID e o valor em estoque do produto com maior valor em estoque, se houver.
+        fn highest_value_product() -> Option<(u64, u64)>;
+
+        /// Exporta os produtos e as vendas, ordenados por ID e código, para backup.
+        fn export_snapshot() -> (Vec<Product>, Vec<Sale>);
+
+        /// Retorna uma página de produtos a partir do cursor informado e o cursor da próxima página.
+        fn products_page(cursor: Option<StorageCursor>, limit: u32) -> (Vec<(u64, Product)>, Option<StorageCursor>);
+
+        /// Retorna as estatísticas agregadas da loja.
+        fn store_stats() -> StoreStats;
+
+        /// Retorna o valor médio das vendas, ou zero se não houver vendas.
+        fn average_sale_value() -> u64;
+
+        /// Retorna as categorias com pelo menos um produto ativo.
+        fn used_categories() -> Vec<Category>;
+
+        /// Retorna verdadeiro se existe um produto com o ID informado.
+        fn product_exists(id: u64) -> bool;
+
+        /// Retorna verdadeiro se existe uma venda com o código informado.
+        fn sale_exists(code: u64) -> bool;
+
+        /// Retorna as unidades do produto vendidas dentro da janela de velocidade de vendas.
+        fn sales_velocity(id: u64) -> u64;
+
+        /// Retorna o tamanho codificado do produto, para estimativa de taxas de armazenamento.
+        fn product_encoded_size(id: u64) -> Option<u32>;
+
+        /// Retorna o recibo detalhado da venda, se ela existir.
+        fn receipt(code: u64) -> Option<Receipt>;
+
+        /// Recalcula o valor total da venda com os preços e a sobretaxa atuais, se ela existir.
+        fn revalue_sale(code: u64) -> Option<u64>;
+    }
+}
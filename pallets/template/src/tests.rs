@@ -2,7 +2,324 @@
 
 use super::*;
 use crate::mock::*;
-use frame_support::{assert_ok, assert_noop};
+use frame_support::{assert_err, assert_ok, assert_noop, dispatch::{GetDispatchInfo, PostDispatchInfo}, pallet_prelude::{DispatchResult, DispatchResultWithPostInfo}, weights::{RuntimeDbWeight, Weight}};
+use codec::Encode;
+
+// Monta uma chamada de `add_product` com valores padrão; cada teste altera apenas os campos que verifica.
+struct ProductBuilder {
+    origin: RuntimeOrigin,
+    name: Vec<u8>,
+    stock: u64,
+    price: u64,
+    amount_to_restock: u64,
+    restock_date: Date,
+    category: Category,
+    metadata_hash: Option<[u8; 32]>,
+    restock_clear_level: Option<u64>,
+    min_order_qty: Option<u64>,
+    max_order_qty: Option<u64>,
+    attributes: Vec<(Vec<u8>, Vec<u8>)>,
+    unit: Option<StockUnitLabel>
+}
+
+impl ProductBuilder {
+    fn new(stock: u64, price: u64, amount_to_restock: u64, category: Category) -> Self {
+        Self {
+            origin: RuntimeOrigin::signed(1),
+            name: b"Test Product".to_vec(),
+            stock,
+            price,
+            amount_to_restock,
+            restock_date: Date::new(1, 1, 2023).unwrap(),
+            category,
+            metadata_hash: None,
+            restock_clear_level: None,
+            min_order_qty: None,
+            max_order_qty: None,
+            attributes: Vec::new(),
+            unit: None
+        }
+    }
+
+    fn origin(mut self, origin: RuntimeOrigin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    fn name(mut self, name: Vec<u8>) -> Self {
+        self.name = name;
+        self
+    }
+
+    fn restock_date(mut self, restock_date: Date) -> Self {
+        self.restock_date = restock_date;
+        self
+    }
+
+    fn metadata_hash(mut self, metadata_hash: [u8; 32]) -> Self {
+        self.metadata_hash = Some(metadata_hash);
+        self
+    }
+
+    fn restock_clear_level(mut self, restock_clear_level: u64) -> Self {
+        self.restock_clear_level = Some(restock_clear_level);
+        self
+    }
+
+    fn min_order_qty(mut self, min_order_qty: u64) -> Self {
+        self.min_order_qty = Some(min_order_qty);
+        self
+    }
+
+    fn max_order_qty(mut self, max_order_qty: u64) -> Self {
+        self.max_order_qty = Some(max_order_qty);
+        self
+    }
+
+    fn attributes(mut self, attributes: Vec<(Vec<u8>, Vec<u8>)>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    fn unit(mut self, unit: StockUnitLabel) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    fn call(self) -> DispatchResult {
+        Pallet::<Test>::add_product(
+            self.origin,
+            self.name,
+            self.stock,
+            self.price,
+            self.amount_to_restock,
+            self.restock_date,
+            self.category,
+            self.metadata_hash,
+            self.restock_clear_level,
+            self.min_order_qty,
+            self.max_order_qty,
+            self.attributes,
+            self.unit
+        )
+    }
+
+    // Adiciona o produto e retorna o ID gerado.
+    fn add(self) -> u64 {
+        assert_ok!(self.call());
+        last_added_product()
+    }
+}
+
+// Monta uma chamada de `update_product` que não altera nenhum campo além dos informados.
+struct ProductUpdate {
+    id: u64,
+    stock: Option<u64>,
+    price: Option<u64>,
+    amount_to_restock: Option<u64>,
+    metadata_hash: Option<Option<[u8; 32]>>,
+    restock_clear_level: Option<Option<u64>>,
+    attributes: Option<Vec<(Vec<u8>, Vec<u8>)>>,
+    unit: Option<StockUnitLabel>
+}
+
+impl ProductUpdate {
+    fn new(id: u64) -> Self {
+        Self {
+            id,
+            stock: None,
+            price: None,
+            amount_to_restock: None,
+            metadata_hash: None,
+            restock_clear_level: None,
+            attributes: None,
+            unit: None
+        }
+    }
+
+    fn stock(mut self, stock: u64) -> Self {
+        self.stock = Some(stock);
+        self
+    }
+
+    fn price(mut self, price: u64) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    fn amount_to_restock(mut self, amount_to_restock: u64) -> Self {
+        self.amount_to_restock = Some(amount_to_restock);
+        self
+    }
+
+    fn metadata_hash(mut self, metadata_hash: Option<[u8; 32]>) -> Self {
+        self.metadata_hash = Some(metadata_hash);
+        self
+    }
+
+    fn restock_clear_level(mut self, restock_clear_level: Option<u64>) -> Self {
+        self.restock_clear_level = Some(restock_clear_level);
+        self
+    }
+
+    fn attributes(mut self, attributes: Vec<(Vec<u8>, Vec<u8>)>) -> Self {
+        self.attributes = Some(attributes);
+        self
+    }
+
+    fn unit(mut self, unit: StockUnitLabel) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    fn call(self) -> DispatchResultWithPostInfo {
+        Pallet::<Test>::update_product(
+            RuntimeOrigin::signed(1),
+            self.id,
+            None,
+            self.stock,
+            self.price,
+            self.amount_to_restock,
+            None,
+            None,
+            self.metadata_hash,
+            self.restock_clear_level,
+            self.attributes,
+            self.unit
+        )
+    }
+}
+
+// Adiciona um produto com os campos essenciais e retorna o ID gerado.
+fn add_test_product(stock: u64, price: u64, amount_to_restock: u64, category: Category) -> u64 {
+    ProductBuilder::new(stock, price, amount_to_restock, category).add()
+}
+
+// Retorna o ID do último produto adicionado, a partir dos eventos emitidos.
+fn last_added_product() -> u64 {
+    System::events()
+        .into_iter()
+        .rev()
+        .find_map(|record| match record.event {
+            RuntimeEvent::TemplateModule(Event::ProductAdded(id)) => Some(id),
+            _ => None,
+        })
+        .expect("no product added")
+}
+
+// Monta uma chamada de `register_sale` com valores padrão; `split` a transforma em `register_split_sale`.
+struct SaleBuilder {
+    origin: RuntimeOrigin,
+    products: Vec<ItemSale>,
+    bundles: Vec<u64>,
+    payment_method: PaymentMethod,
+    payments: Option<Vec<(PaymentMethod, u64)>>,
+    date: Date,
+    gift_card: Option<[u8; 16]>,
+    currency: Option<[u8; 3]>,
+    is_member: bool
+}
+
+impl SaleBuilder {
+    fn new(products: Vec<ItemSale>, date: Date) -> Self {
+        Self {
+            origin: RuntimeOrigin::signed(1),
+            products,
+            bundles: Vec::new(),
+            payment_method: PaymentMethod::Credit,
+            payments: None,
+            date,
+            gift_card: None,
+            currency: None,
+            is_member: false
+        }
+    }
+
+    fn origin(mut self, origin: RuntimeOrigin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    fn bundles(mut self, bundles: Vec<u64>) -> Self {
+        self.bundles = bundles;
+        self
+    }
+
+    fn payment_method(mut self, payment_method: PaymentMethod) -> Self {
+        self.payment_method = payment_method;
+        self
+    }
+
+    fn split(mut self, payments: Vec<(PaymentMethod, u64)>) -> Self {
+        self.payments = Some(payments);
+        self
+    }
+
+    fn gift_card(mut self, gift_card: [u8; 16]) -> Self {
+        self.gift_card = Some(gift_card);
+        self
+    }
+
+    fn currency(mut self, currency: [u8; 3]) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    fn member(mut self, is_member: bool) -> Self {
+        self.is_member = is_member;
+        self
+    }
+
+    fn call(self) -> DispatchResult {
+        match self.payments {
+            Some(payments) => Pallet::<Test>::register_split_sale(
+                self.origin,
+                b"Test Seller".to_vec(),
+                self.products,
+                self.bundles,
+                payments,
+                self.date,
+                self.gift_card,
+                self.currency,
+                self.is_member
+            ),
+            None => Pallet::<Test>::register_sale(
+                self.origin,
+                b"Test Seller".to_vec(),
+                self.products,
+                self.bundles,
+                self.payment_method,
+                self.date,
+                self.gift_card,
+                self.currency,
+                self.is_member
+            )
+        }
+    }
+
+    // Registra a venda e retorna o código gerado.
+    fn register(self) -> u64 {
+        assert_ok!(self.call());
+        last_registered_sale()
+    }
+}
+
+// Registra uma venda dos itens informados na data fornecida e retorna o código gerado.
+fn register_test_sale(products: Vec<ItemSale>, date: Date) -> u64 {
+    SaleBuilder::new(products, date).register()
+}
+
+// Retorna o código da venda registrada mais recentemente, a partir dos eventos emitidos.
+fn last_registered_sale() -> u64 {
+    System::events()
+        .into_iter()
+        .rev()
+        .find_map(|record| match record.event {
+            RuntimeEvent::TemplateModule(Event::SaleRegistered(code)) => Some(code),
+            _ => None,
+        })
+        .expect("no sale registered")
+}
 
 #[test]
 fn it_adds_a_product() {
@@ -21,7 +338,13 @@ fn it_adds_a_product() {
             price,
             amount_to_restock,
             restock_date.clone(),
-            category.clone()
+            category.clone(),
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         let product = Products::<Test>::get(0).unwrap();
@@ -45,7 +368,13 @@ fn it_adds_a_product() {
             price,
             amount_to_restock,
             invalid_date,
-            category.clone()),
+            category.clone(),
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None),
             Error::<Test>::InvalidDate
         );
     });
@@ -68,7 +397,13 @@ fn it_gets_a_product() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         assert_ok!(Pallet::<Test>::get_product(RuntimeOrigin::signed(1), 0));
@@ -102,7 +437,13 @@ fn it_lists_products_to_restock() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         let product_name = b"Test Product".to_vec();
@@ -119,7 +460,13 @@ fn it_lists_products_to_restock() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         assert_ok!(Pallet::<Test>::list_products_to_restock(RuntimeOrigin::signed(1)));
@@ -143,7 +490,13 @@ fn it_lists_all_products() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         let product_name = b"Test Product".to_vec();
@@ -160,7 +513,13 @@ fn it_lists_all_products() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         assert_ok!(Pallet::<Test>::list_all_products(RuntimeOrigin::signed(1)));
@@ -184,7 +543,13 @@ fn it_updates_a_product() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         let new_name = b"Updated Product".to_vec();
@@ -196,7 +561,11 @@ fn it_updates_a_product() {
             Some(100),
             Some(30),
             Some(Date::new(1, 1, 2024).unwrap()),
-			Some(Category::Food)
+            Some(Category::Food),
+            None,
+            None,
+            None,
+            None
         ));
 
         let product = Products::<Test>::get(0).unwrap();
@@ -208,7 +577,7 @@ fn it_updates_a_product() {
 fn it_fails_to_update_a_nonexistent_product() {
     new_test_ext().execute_with(|| {
         assert_noop!(
-            Pallet::<Test>::update_product(RuntimeOrigin::signed(1), 999, None, None, None, None, None, None),
+            Pallet::<Test>::update_product(RuntimeOrigin::signed(1), 999, None, None, None, None, None, None, None, None, None, None),
             Error::<Test>::ProductNotFound
         );
     });
@@ -231,7 +600,13 @@ fn it_removes_a_product() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), 0));
@@ -259,18 +634,29 @@ fn it_registers_a_sale() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         let seller = b"Test Seller".to_vec();
-        let products = vec![ItemSale { product_id: 0, amount: 2 }];
+        let products = vec![ItemSale { product_id: 0, amount: 2, discount_percent: None }];
         let payment_method = PaymentMethod::Credit;
 
         assert_ok!(Pallet::<Test>::register_sale(
             RuntimeOrigin::signed(1),
             seller.clone(),
             products.clone(),
-            payment_method
+            Vec::new(),
+            payment_method,
+            Date::new(3, 2, 2025).unwrap(),
+            None,
+            None,
+            false
         ));
 
         let sale = Sales::<Test>::get(0).unwrap();
@@ -297,18 +683,29 @@ fn it_gets_a_sale() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         let seller = b"Test Seller".to_vec();
-        let products = vec![ItemSale { product_id: 0, amount: 2 }];
+        let products = vec![ItemSale { product_id: 0, amount: 2, discount_percent: None }];
         let payment_method = PaymentMethod::Credit;
 
         assert_ok!(Pallet::<Test>::register_sale(
             RuntimeOrigin::signed(1),
             seller.clone(),
             products.clone(),
-            payment_method
+            Vec::new(),
+            payment_method,
+            Date::new(3, 2, 2025).unwrap(),
+            None,
+            None,
+            false
         ));
 
         assert_ok!(Pallet::<Test>::get_sale(RuntimeOrigin::signed(1), 0));
@@ -332,15 +729,21 @@ fn it_fails_to_register_a_sale_with_insufficient_stock() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         let seller = b"Test Seller".to_vec();
-        let products = vec![ItemSale { product_id: 0, amount: 2 }];
+        let products = vec![ItemSale { product_id: 0, amount: 2, discount_percent: None }];
         let payment_method = PaymentMethod::Credit;
 
         assert_noop!(
-            Pallet::<Test>::register_sale(RuntimeOrigin::signed(1), seller, products, payment_method),
+            Pallet::<Test>::register_sale(RuntimeOrigin::signed(1), seller, products, Vec::new(), payment_method, Date::new(3, 2, 2025).unwrap(), None, None, false),
             Error::<Test>::InsufficientStock
         );
     });
@@ -350,11 +753,11 @@ fn it_fails_to_register_a_sale_with_insufficient_stock() {
 fn it_fails_to_register_a_sale_with_nonexistent_product() {
     new_test_ext().execute_with(|| {
         let seller = b"Test Seller".to_vec();
-        let products = vec![ItemSale { product_id: 999, amount: 1 }];
+        let products = vec![ItemSale { product_id: 999, amount: 1, discount_percent: None }];
         let payment_method = PaymentMethod::Credit;
 
         assert_noop!(
-            Pallet::<Test>::register_sale(RuntimeOrigin::signed(1), seller, products, payment_method),
+            Pallet::<Test>::register_sale(RuntimeOrigin::signed(1), seller, products, Vec::new(), payment_method, Date::new(3, 2, 2025).unwrap(), None, None, false),
             Error::<Test>::ProductNotFound
         );
     });
@@ -377,18 +780,29 @@ fn it_lists_all_sales() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         let seller = b"Test Seller".to_vec();
-        let products = vec![ItemSale { product_id: 0, amount: 2 }];
+        let products = vec![ItemSale { product_id: 0, amount: 2, discount_percent: None }];
         let payment_method = PaymentMethod::Credit;
 
         assert_ok!(Pallet::<Test>::register_sale(
             RuntimeOrigin::signed(1),
             seller.clone(),
             products.clone(),
-            payment_method
+            Vec::new(),
+            payment_method,
+            Date::new(3, 2, 2025).unwrap(),
+            None,
+            None,
+            false
         ));
 
         assert_ok!(Pallet::<Test>::list_all_sales(RuntimeOrigin::signed(1)));
@@ -412,18 +826,29 @@ fn it_updates_a_sale() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         let seller = b"Test Seller".to_vec();
-        let products = vec![ItemSale { product_id: 0, amount: 2 }];
+        let products = vec![ItemSale { product_id: 0, amount: 2, discount_percent: None }];
         let payment_method = PaymentMethod::Credit;
 
         assert_ok!(Pallet::<Test>::register_sale(
             RuntimeOrigin::signed(1),
             seller.clone(),
             products.clone(),
-            payment_method
+            Vec::new(),
+            payment_method,
+            Date::new(3, 2, 2025).unwrap(),
+            None,
+            None,
+            false
         ));
 
         let new_seller = b"Updated Seller".to_vec();
@@ -470,18 +895,29 @@ fn it_removes_a_sale() {
             price,
             amount_to_restock,
             restock_date,
-            category
+            category,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            None
         ));
 
         let seller = b"Test Seller".to_vec();
-        let products = vec![ItemSale { product_id: 0, amount: 2 }];
+        let products = vec![ItemSale { product_id: 0, amount: 2, discount_percent: None }];
         let payment_method = PaymentMethod::Credit;
 
         assert_ok!(Pallet::<Test>::register_sale(
             RuntimeOrigin::signed(1),
             seller.clone(),
             products.clone(),
-            payment_method
+            Vec::new(),
+            payment_method,
+            Date::new(3, 2, 2025).unwrap(),
+            None,
+            None,
+            false
         ));
 
         assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), 0));
@@ -504,5 +940,3589 @@ fn it_fails_to_remove_a_nonexistent_sale() {
 
 #[test]
 fn test_max_encoded_len() {
-    assert_eq!(total_max_encoded_len(), 662);
-}
\ No newline at end of file
+    assert_eq!(total_max_encoded_len::<Test>(), 1807);
+}
+
+#[test]
+fn it_checks_if_a_product_is_sellable() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(10, 50, 5, Category::Electronic);
+
+        assert!(Pallet::<Test>::is_sellable(id, 1));
+        assert!(Pallet::<Test>::is_sellable(id, 10));
+
+        // Estoque insuficiente
+        assert!(!Pallet::<Test>::is_sellable(id, 11));
+
+        // Produto inexistente
+        assert!(!Pallet::<Test>::is_sellable(999, 1));
+
+        // Produto removido
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), id));
+        assert!(!Pallet::<Test>::is_sellable(id, 1));
+    });
+}
+
+#[test]
+fn it_applies_the_sale_validations_when_checking_if_a_product_is_sellable() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 50, 5, Category::Electronic);
+        Products::<Test>::mutate(id, |product| {
+            let product = product.as_mut().unwrap();
+            product.min_order_qty = Some(2);
+            product.max_order_qty = Some(10);
+        });
+
+        assert!(!Pallet::<Test>::is_sellable(id, 0));
+        assert!(!Pallet::<Test>::is_sellable(id, 1));
+        assert!(!Pallet::<Test>::is_sellable(id, 11));
+        assert!(Pallet::<Test>::is_sellable(id, 2));
+
+        // Produto travado
+        assert_ok!(Pallet::<Test>::lock_product(RuntimeOrigin::root(), id));
+        assert!(!Pallet::<Test>::is_sellable(id, 2));
+
+        // Quantidade fracionária de um produto vendido por peça
+        StockUnit::set(1_000);
+        let pieces = add_test_product(5_000, 200, 1_000, Category::Misc);
+        assert!(!Pallet::<Test>::is_sellable(pieces, 1_500));
+        assert!(Pallet::<Test>::is_sellable(pieces, 2_000));
+    });
+}
+
+#[test]
+fn it_tracks_daily_revenue() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 50, 20, Category::Food);
+        let first_day = Date::new(3, 2, 2025).unwrap();
+        let second_day = Date::new(4, 2, 2025).unwrap();
+
+        register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], first_day.clone());
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], first_day.clone());
+        register_test_sale(vec![ItemSale { product_id: id, amount: 4, discount_percent: None }], second_day.clone());
+
+        assert_eq!(DailyRevenue::<Test>::get(&first_day), 150);
+        assert_eq!(DailyRevenue::<Test>::get(&second_day), 200);
+
+        assert_ok!(Pallet::<Test>::get_daily_revenue(RuntimeOrigin::signed(1), first_day.clone()));
+        System::assert_last_event(Event::DailyRevenueGotten(first_day.clone(), 150).into());
+
+        // Remover uma venda estorna o valor na data correspondente
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), code));
+        assert_eq!(DailyRevenue::<Test>::get(&first_day), 100);
+        assert_eq!(DailyRevenue::<Test>::get(&second_day), 200);
+    });
+}
+
+#[test]
+fn it_sets_and_clears_the_metadata_hash() {
+    new_test_ext().execute_with(|| {
+        let hash = [7u8; 32];
+
+        assert_ok!(ProductBuilder::new(10, 50, 5, Category::Electronic).metadata_hash(hash).call());
+        assert_eq!(Products::<Test>::get(0).unwrap().metadata_hash, Some(hash));
+
+        // Atualização sem o campo mantém o hash
+        assert_ok!(ProductUpdate::new(0).stock(20).call());
+        assert_eq!(Products::<Test>::get(0).unwrap().metadata_hash, Some(hash));
+
+        // Limpeza do hash
+        assert_ok!(ProductUpdate::new(0).metadata_hash(None).call());
+        assert_eq!(Products::<Test>::get(0).unwrap().metadata_hash, None);
+    });
+}
+
+#[test]
+fn it_limits_the_restock_amount() {
+    new_test_ext().execute_with(|| {
+        // No limite configurado
+        let id = add_test_product(10, 50, 1_000, Category::Misc);
+        assert_eq!(Products::<Test>::get(id).unwrap().amount_to_restock, 1_000);
+
+        // Acima do limite
+        assert_noop!(
+            ProductBuilder::new(10, 50, 1_001, Category::Misc).call(),
+            Error::<Test>::RestockAmountTooLarge
+        );
+
+        assert_ok!(ProductUpdate::new(id).amount_to_restock(1_000).call());
+        assert_noop!(
+            ProductUpdate::new(id).amount_to_restock(1_001).call(),
+            Error::<Test>::RestockAmountTooLarge
+        );
+    });
+}
+
+#[test]
+fn it_transfers_stock_between_products() {
+    new_test_ext().execute_with(|| {
+        let from = add_test_product(10, 50, 5, Category::Clothing);
+        let to = add_test_product(2, 50, 5, Category::Clothing);
+
+        assert_ok!(Pallet::<Test>::transfer_stock(RuntimeOrigin::signed(1), from, to, 4));
+        System::assert_last_event(Event::StockTransferred(from, to, 4).into());
+
+        assert_eq!(Products::<Test>::get(from).unwrap().stock, 6);
+        assert_eq!(Products::<Test>::get(to).unwrap().stock, 6);
+    });
+}
+
+#[test]
+fn it_fails_to_transfer_more_stock_than_available() {
+    new_test_ext().execute_with(|| {
+        let from = add_test_product(10, 50, 5, Category::Clothing);
+        let to = add_test_product(2, 50, 5, Category::Clothing);
+
+        assert_noop!(
+            Pallet::<Test>::transfer_stock(RuntimeOrigin::signed(1), from, to, 11),
+            Error::<Test>::InsufficientStock
+        );
+        assert_noop!(
+            Pallet::<Test>::transfer_stock(RuntimeOrigin::signed(1), from, from, 1),
+            Error::<Test>::SameProduct
+        );
+        assert_noop!(
+            Pallet::<Test>::transfer_stock(RuntimeOrigin::signed(1), from, 999, 1),
+            Error::<Test>::ProductNotFound
+        );
+
+        assert_eq!(Products::<Test>::get(from).unwrap().stock, 10);
+        assert_eq!(Products::<Test>::get(to).unwrap().stock, 2);
+    });
+}
+
+#[test]
+fn it_emits_listing_payloads_by_default() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(5, 50, 10, Category::Food);
+        let product = Products::<Test>::get(id).unwrap();
+
+        assert_ok!(Pallet::<Test>::list_all_products(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsListed(vec![product.clone()]).into());
+
+        assert_ok!(Pallet::<Test>::list_products_to_restock(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsToRestock(vec![product]).into());
+
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        let sale = Sales::<Test>::get(code).unwrap();
+
+        assert_ok!(Pallet::<Test>::list_all_sales(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::SalesListed(vec![sale]).into());
+    });
+}
+
+#[test]
+fn it_emits_only_counts_when_listing_payloads_are_disabled() {
+    new_test_ext().execute_with(|| {
+        EmitListingPayloads::set(false);
+
+        let id = add_test_product(5, 50, 10, Category::Food);
+        add_test_product(50, 50, 10, Category::Food);
+
+        assert_ok!(Pallet::<Test>::list_all_products(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsListedCount(2).into());
+
+        assert_ok!(Pallet::<Test>::list_products_to_restock(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsToRestockCount(1).into());
+
+        register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+
+        assert_ok!(Pallet::<Test>::list_all_sales(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::SalesListedCount(1).into());
+    });
+}
+
+#[test]
+fn it_finds_the_cheapest_product_in_a_category() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Pallet::<Test>::cheapest_in_category(Category::Food, false), None);
+
+        add_test_product(10, 80, 5, Category::Food);
+        let cheapest = add_test_product(10, 30, 5, Category::Food);
+        add_test_product(10, 30, 5, Category::Food);
+        let out_of_stock = add_test_product(0, 10, 5, Category::Food);
+        add_test_product(10, 1, 5, Category::Electronic);
+
+        // Produto sem estoque considerado
+        assert_eq!(Pallet::<Test>::cheapest_in_category(Category::Food, false), Some((out_of_stock, 10)));
+
+        // Empate de preço resolvido pelo menor ID
+        assert_eq!(Pallet::<Test>::cheapest_in_category(Category::Food, true), Some((cheapest, 30)));
+
+        assert_eq!(Pallet::<Test>::cheapest_in_category(Category::Clothing, false), None);
+    });
+}
+
+#[test]
+fn it_repairs_the_next_product_id() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(10, 50, 5, Category::Misc);
+        let product = Products::<Test>::get(id).unwrap();
+
+        // Inserção fora do fluxo normal, sem atualizar o contador
+        Products::<Test>::insert(41, product);
+        assert_eq!(Pallet::<Test>::next_product_id(), 1);
+
+        assert_noop!(
+            Pallet::<Test>::repair_next_product_id(RuntimeOrigin::signed(1)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        assert_ok!(Pallet::<Test>::repair_next_product_id(RuntimeOrigin::root()));
+        System::assert_last_event(Event::ProductCounterRepaired(42).into());
+        assert_eq!(Pallet::<Test>::next_product_id(), 42);
+        assert_eq!(add_test_product(10, 50, 5, Category::Misc), 42);
+    });
+}
+
+#[test]
+fn it_repairs_the_next_sale_code() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(10, 50, 5, Category::Misc);
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+
+        let sale = Sales::<Test>::get(code).unwrap();
+        Sales::<Test>::insert(7, sale);
+
+        assert_ok!(Pallet::<Test>::repair_next_sale_code(RuntimeOrigin::root()));
+        System::assert_last_event(Event::SaleCounterRepaired(8).into());
+        assert_eq!(register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap()), 8);
+    });
+}
+
+// Conta quantas vezes o evento informado foi emitido.
+fn count_events(event: Event<Test>) -> usize {
+    let event: RuntimeEvent = event.into();
+    System::events().into_iter().filter(|record| record.event == event).count()
+}
+
+#[test]
+fn it_applies_hysteresis_to_restock_alerts() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ProductBuilder::new(20, 50, 10, Category::Food).restock_clear_level(15).call());
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        // A venda que cruza o limite dispara o alerta
+        register_test_sale(vec![ItemSale { product_id: 0, amount: 11, discount_percent: None }], date.clone());
+        assert_eq!(count_events(Event::ProductNeedsRestock(0)), 1);
+        assert!(Pallet::<Test>::alert_state(0));
+
+        // Reposição abaixo do nível de encerramento não encerra o alerta
+        assert_ok!(ProductUpdate::new(0).stock(14).call());
+        assert!(Pallet::<Test>::alert_state(0));
+        register_test_sale(vec![ItemSale { product_id: 0, amount: 5, discount_percent: None }], date.clone());
+        assert_eq!(count_events(Event::ProductNeedsRestock(0)), 1);
+
+        // Reposição acima do nível de encerramento encerra o alerta
+        assert_ok!(ProductUpdate::new(0).stock(16).call());
+        System::assert_has_event(Event::RestockAlertCleared(0).into());
+        assert!(!Pallet::<Test>::alert_state(0));
+
+        // Cruzar o limite novamente dispara um novo alerta
+        register_test_sale(vec![ItemSale { product_id: 0, amount: 7, discount_percent: None }], date);
+        assert_eq!(count_events(Event::ProductNeedsRestock(0)), 2);
+    });
+}
+
+#[test]
+fn it_rejects_a_clear_level_below_the_restock_threshold() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ProductBuilder::new(20, 50, 10, Category::Food).restock_clear_level(9).call(),
+            Error::<Test>::InvalidClearLevel
+        );
+    });
+}
+
+#[test]
+fn it_lists_products_below_a_stock_level() {
+    new_test_ext().execute_with(|| {
+        for stock in [0, 5, 9, 10, 25] {
+            add_test_product(stock, 50, 1, Category::Misc);
+        }
+
+        assert_ok!(Pallet::<Test>::list_products_below_stock(RuntimeOrigin::signed(1), 10, 10));
+
+        let mut stocks: Vec<u64> = match System::events().last().unwrap().event.clone() {
+            RuntimeEvent::TemplateModule(Event::ProductsBelowStock(products)) => products.iter().map(|product| product.stock).collect(),
+            _ => panic!("unexpected event"),
+        };
+        stocks.sort();
+        assert_eq!(stocks, vec![0, 5, 9]);
+
+        // O limite restringe a quantidade de produtos retornados
+        EmitListingPayloads::set(false);
+        assert_ok!(Pallet::<Test>::list_products_below_stock(RuntimeOrigin::signed(1), 10, 2));
+        System::assert_last_event(Event::ProductsBelowStockCount(2).into());
+    });
+}
+
+#[test]
+fn it_reassigns_a_category() {
+    new_test_ext().execute_with(|| {
+        let first = add_test_product(10, 50, 5, Category::Misc);
+        let second = add_test_product(10, 50, 5, Category::Misc);
+        let electronic = add_test_product(10, 50, 5, Category::Electronic);
+        let food = add_test_product(10, 50, 5, Category::Food);
+
+        assert_noop!(
+            Pallet::<Test>::reassign_category(RuntimeOrigin::root(), Category::Misc, Category::Misc),
+            Error::<Test>::SameCategory
+        );
+
+        assert_ok!(Pallet::<Test>::reassign_category(RuntimeOrigin::root(), Category::Misc, Category::Electronic));
+        System::assert_last_event(Event::CategoryReassigned(Category::Misc, Category::Electronic, 2).into());
+
+        for id in [first, second, electronic] {
+            assert_eq!(Products::<Test>::get(id).unwrap().category, Category::Electronic);
+            assert!(ProductsByCategory::<Test>::contains_key(Category::Electronic, id));
+        }
+        assert_eq!(Products::<Test>::get(food).unwrap().category, Category::Food);
+        assert_eq!(ProductsByCategory::<Test>::iter_prefix(Category::Misc).count(), 0);
+        assert_eq!(ProductsByCategory::<Test>::iter_prefix(Category::Electronic).count(), 3);
+    });
+}
+
+#[test]
+fn it_keeps_caps_and_locked_products_when_reassigning_a_category() {
+    new_test_ext().execute_with(|| {
+        let locked = add_test_product(10, 50, 5, Category::Misc);
+        let free = add_test_product(20, 50, 5, Category::Misc);
+        add_test_product(5, 50, 5, Category::Food);
+        assert_ok!(Pallet::<Test>::lock_product(RuntimeOrigin::root(), locked));
+
+        // Os 20 itens do produto livre não cabem no limite de 24 com os 5 já existentes
+        assert_ok!(Pallet::<Test>::set_category_stock_cap(RuntimeOrigin::root(), Category::Food, Some(24)));
+        assert_noop!(
+            Pallet::<Test>::reassign_category(RuntimeOrigin::root(), Category::Misc, Category::Food),
+            Error::<Test>::CategoryCapExceeded
+        );
+
+        // O produto travado não é movido nem contado
+        assert_ok!(Pallet::<Test>::set_category_stock_cap(RuntimeOrigin::root(), Category::Food, Some(25)));
+        assert_ok!(Pallet::<Test>::reassign_category(RuntimeOrigin::root(), Category::Misc, Category::Food));
+        System::assert_last_event(Event::CategoryReassigned(Category::Misc, Category::Food, 1).into());
+        assert_eq!(Products::<Test>::get(locked).unwrap().category, Category::Misc);
+        assert_eq!(Products::<Test>::get(free).unwrap().category, Category::Food);
+        assert_eq!(Pallet::<Test>::category_stock_total(Category::Misc), 10);
+        assert_eq!(Pallet::<Test>::category_stock_total(Category::Food), 25);
+        assert!(ProductsByCategory::<Test>::contains_key(Category::Misc, locked));
+    });
+}
+
+#[test]
+fn it_applies_per_line_discounts() {
+    new_test_ext().execute_with(|| {
+        let discounted = add_test_product(10, 100, 1, Category::Clothing);
+        let full_price = add_test_product(10, 50, 1, Category::Clothing);
+
+        let code = register_test_sale(
+            vec![
+                ItemSale { product_id: discounted, amount: 2, discount_percent: Some(10) },
+                ItemSale { product_id: full_price, amount: 1, discount_percent: None },
+            ],
+            Date::new(3, 2, 2025).unwrap()
+        );
+
+        let sale = Sales::<Test>::get(code).unwrap();
+        assert_eq!(sale.value, 230);
+        assert_eq!(sale.lines, vec![
+            SaleLine { product_id: discounted, amount: 2, unit_price: 100, discount_percent: Some(10), price_floor: None, value: 180, category: Category::Clothing, tax: 18, consigned: 0 },
+            SaleLine { product_id: full_price, amount: 1, unit_price: 50, discount_percent: None, price_floor: None, value: 50, category: Category::Clothing, tax: 5, consigned: 0 },
+        ]);
+
+        assert_noop!(
+            SaleBuilder::new(vec![ItemSale { product_id: full_price, amount: 1, discount_percent: Some(101) }], Date::new(3, 2, 2025).unwrap())
+                .call(),
+            Error::<Test>::InvalidDiscount
+        );
+    });
+}
+
+#[test]
+fn it_bounds_the_price_history_by_count() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(10, 50, 5, Category::Misc);
+
+        for price in [60, 70, 80] {
+            assert_ok!(ProductUpdate::new(id).price(price).call());
+        }
+
+        let prices: Vec<u64> = Pallet::<Test>::price_history(id).iter().map(|(_, price)| *price).collect();
+        assert_eq!(prices, vec![60, 70, 80]);
+    });
+}
+
+#[test]
+fn it_prunes_stale_price_history_entries() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(10, 50, 5, Category::Misc);
+
+        System::set_block_number(5);
+        assert_ok!(ProductUpdate::new(id).price(60).call());
+        assert_eq!(Pallet::<Test>::price_history(id).into_inner(), vec![(1, 50), (5, 60)]);
+
+        // O registro do bloco 1 expira, o do bloco 5 ainda está dentro da idade máxima
+        System::set_block_number(12);
+        assert_ok!(ProductUpdate::new(id).price(70).call());
+        assert_eq!(Pallet::<Test>::price_history(id).into_inner(), vec![(5, 60), (12, 70)]);
+
+        System::set_block_number(30);
+        assert_ok!(ProductUpdate::new(id).price(80).call());
+        assert_eq!(Pallet::<Test>::price_history(id).into_inner(), vec![(30, 80)]);
+    });
+}
+
+#[test]
+fn it_ranks_the_top_buyers() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 50, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        for (buyer, amount) in [(1, 2), (2, 4), (2, 2)] {
+            assert_ok!(SaleBuilder::new(vec![ItemSale { product_id: id, amount, discount_percent: None }], date.clone())
+                .origin(RuntimeOrigin::signed(buyer))
+                .payment_method(PaymentMethod::Money)
+                .call());
+        }
+
+        assert_eq!(Pallet::<Test>::buyer_spend(1), 100);
+        assert_eq!(Pallet::<Test>::buyer_spend(2), 300);
+
+        assert_ok!(Pallet::<Test>::top_buyers(RuntimeOrigin::signed(1), 5));
+        System::assert_last_event(Event::TopBuyers(vec![(2, 300), (1, 100)]).into());
+
+        assert_ok!(Pallet::<Test>::top_buyers(RuntimeOrigin::signed(1), 1));
+        System::assert_last_event(Event::TopBuyers(vec![(2, 300)]).into());
+
+        // Remover a venda estorna o gasto do comprador
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), 1));
+        assert_eq!(Pallet::<Test>::buyer_spend(2), 100);
+    });
+}
+
+#[test]
+fn it_accumulates_buyer_spend_beyond_u64() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 50, 5, Category::Misc);
+        BuyerSpend::<Test>::insert(1, u64::MAX as u128);
+
+        register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+
+        assert_eq!(Pallet::<Test>::buyer_spend(1), u64::MAX as u128 + 100);
+    });
+}
+
+#[test]
+fn it_lists_sales_within_a_block_range() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 50, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        let mut codes = Vec::new();
+        for block in [1, 3, 5, 8] {
+            System::set_block_number(block);
+            codes.push(register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], date.clone()));
+        }
+
+        assert_eq!(Pallet::<Test>::sale_created_at(codes[1]), Some(3));
+        assert_eq!(Pallet::<Test>::sales_in_block_range(3, 5), vec![codes[1], codes[2]]);
+        assert_eq!(Pallet::<Test>::sales_in_block_range(1, 8), codes);
+        assert_eq!(Pallet::<Test>::sales_in_block_range(6, 7), Vec::<u64>::new());
+    });
+}
+
+#[test]
+fn it_rejects_a_repeated_item_without_enough_stock() {
+    new_test_ext().execute_with(|| {
+        let available = add_test_product(10, 50, 1, Category::Food);
+        let short = add_test_product(3, 50, 1, Category::Food);
+
+        // O segundo item do mesmo produto já não encontra estoque suficiente
+        let items = vec![
+            ItemSale { product_id: available, amount: 2, discount_percent: None },
+            ItemSale { product_id: short, amount: 2, discount_percent: None },
+            ItemSale { product_id: short, amount: 2, discount_percent: None },
+        ];
+        assert_noop!(
+            SaleBuilder::new(items, Date::new(3, 2, 2025).unwrap()).call(),
+            Error::<Test>::InsufficientStock
+        );
+    });
+}
+
+#[test]
+fn it_keeps_ids_monotonic_without_recycling() {
+    new_test_ext().execute_with(|| {
+        let first = add_test_product(10, 50, 5, Category::Misc);
+        add_test_product(10, 50, 5, Category::Misc);
+
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), first));
+        assert!(Pallet::<Test>::free_product_ids().is_empty());
+
+        assert_eq!(add_test_product(10, 50, 5, Category::Misc), 2);
+    });
+}
+
+#[test]
+fn it_recycles_removed_product_ids() {
+    new_test_ext().execute_with(|| {
+        RecycleIds::set(true);
+
+        let first = add_test_product(10, 50, 5, Category::Misc);
+        add_test_product(10, 50, 5, Category::Misc);
+
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), first));
+        assert_eq!(Pallet::<Test>::free_product_ids().into_inner(), vec![first]);
+
+        // O ID livre é usado antes de avançar o contador
+        assert_eq!(add_test_product(10, 50, 5, Category::Misc), first);
+        assert_eq!(Pallet::<Test>::next_product_id(), 2);
+        assert_eq!(add_test_product(10, 50, 5, Category::Misc), 2);
+    });
+}
+
+#[test]
+fn it_limits_the_sales_per_block() {
+    new_test_ext().execute_with(|| {
+        MaxSalesPerBlock::set(2);
+
+        let id = add_test_product(100, 50, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+        let items = vec![ItemSale { product_id: id, amount: 1, discount_percent: None }];
+
+        register_test_sale(items.clone(), date.clone());
+        register_test_sale(items.clone(), date.clone());
+        assert_eq!(Pallet::<Test>::sales_this_block(), 2);
+
+        assert_noop!(
+            SaleBuilder::new(items.clone(), date.clone()).call(),
+            Error::<Test>::SaleRateLimited
+        );
+
+        // O contador é zerado no bloco seguinte
+        run_to_block(2);
+        assert_eq!(Pallet::<Test>::sales_this_block(), 0);
+        register_test_sale(items, date);
+        assert_eq!(Pallet::<Test>::sales_this_block(), 1);
+    });
+}
+
+#[test]
+fn it_pays_with_a_gift_card() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 30, 5, Category::Misc);
+        let card = [1u8; 16];
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        assert_ok!(Pallet::<Test>::issue_gift_card(RuntimeOrigin::root(), card, 100));
+        assert_noop!(
+            Pallet::<Test>::issue_gift_card(RuntimeOrigin::root(), card, 50),
+            Error::<Test>::GiftCardAlreadyExists
+        );
+
+        // Pagamento integral com o cartão
+        assert_ok!(SaleBuilder::new(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], date.clone()).gift_card(card).call());
+        System::assert_last_event(Event::SaleRegistered(0).into());
+        System::assert_has_event(Event::GiftCardRedeemed(card, 60, 40).into());
+        assert_eq!(Sales::<Test>::get(0).unwrap().gift_card, Some((card, 60)));
+
+        // Pagamento parcial: o cartão cobre apenas o saldo restante
+        assert_ok!(SaleBuilder::new(vec![ItemSale { product_id: id, amount: 3, discount_percent: None }], date.clone()).gift_card(card).call());
+        assert_eq!(Sales::<Test>::get(1).unwrap().gift_card, Some((card, 40)));
+        assert_eq!(Pallet::<Test>::gift_cards(card), Some(0));
+    });
+}
+
+#[test]
+fn it_rejects_an_unknown_gift_card() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 30, 5, Category::Misc);
+
+        assert_noop!(
+            SaleBuilder::new(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], Date::new(3, 2, 2025).unwrap())
+                .gift_card([9u8; 16])
+                .call(),
+            Error::<Test>::InvalidGiftCard
+        );
+    });
+}
+
+#[test]
+fn it_converts_the_sale_total_to_the_display_currency() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 101, 5, Category::Misc);
+        let eur = *b"EUR";
+
+        // Uma unidade de EUR vale duas unidades da moeda base
+        assert_ok!(Pallet::<Test>::set_exchange_rate(RuntimeOrigin::root(), eur, Some(20_000)));
+        System::assert_last_event(Event::ExchangeRateSet(eur, Some(20_000)).into());
+
+        assert_ok!(SaleBuilder::new(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap())
+            .currency(eur)
+            .call());
+
+        // 101 / 2 = 50,5, arredondado para o mais próximo
+        let sale = Sales::<Test>::get(0).unwrap();
+        assert_eq!(sale.value, 101);
+        assert_eq!(sale.display_currency, Some((eur, 51)));
+
+        Rounding::set(RoundingMode::Down);
+        assert_ok!(SaleBuilder::new(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap())
+            .currency(eur)
+            .call());
+        assert_eq!(Sales::<Test>::get(1).unwrap().display_currency, Some((eur, 50)));
+    });
+}
+
+#[test]
+fn it_rejects_an_unknown_currency() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 30, 5, Category::Misc);
+
+        assert_noop!(
+            Pallet::<Test>::set_exchange_rate(RuntimeOrigin::root(), *b"USD", Some(0)),
+            Error::<Test>::InvalidExchangeRate
+        );
+
+        assert_noop!(
+            SaleBuilder::new(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], Date::new(3, 2, 2025).unwrap())
+                .currency(*b"USD")
+                .call(),
+            Error::<Test>::UnknownCurrency
+        );
+    });
+}
+
+#[test]
+fn it_sells_a_bundle_as_its_components() {
+    new_test_ext().execute_with(|| {
+        let console = add_test_product(10, 300, 1, Category::Electronic);
+        let controller = add_test_product(20, 50, 1, Category::Electronic);
+
+        assert_ok!(Pallet::<Test>::create_bundle(
+            RuntimeOrigin::signed(1),
+            vec![
+                ItemSale { product_id: console, amount: 1, discount_percent: None },
+                ItemSale { product_id: controller, amount: 2, discount_percent: None },
+            ]
+        ));
+        System::assert_last_event(Event::BundleCreated(0).into());
+
+        assert_ok!(SaleBuilder::new(Vec::new(), Date::new(3, 2, 2025).unwrap()).bundles(vec![0, 0]).call());
+
+        assert_eq!(Products::<Test>::get(console).unwrap().stock, 8);
+        assert_eq!(Products::<Test>::get(controller).unwrap().stock, 16);
+        assert_eq!(Sales::<Test>::get(0).unwrap().value, 2 * (300 + 2 * 50));
+    });
+}
+
+#[test]
+fn it_rejects_a_bundle_with_components_out_of_stock() {
+    new_test_ext().execute_with(|| {
+        let console = add_test_product(10, 300, 1, Category::Electronic);
+        let controller = add_test_product(1, 50, 1, Category::Electronic);
+
+        assert_ok!(Pallet::<Test>::create_bundle(
+            RuntimeOrigin::signed(1),
+            vec![
+                ItemSale { product_id: console, amount: 1, discount_percent: None },
+                ItemSale { product_id: controller, amount: 2, discount_percent: None },
+            ]
+        ));
+
+        assert_err!(
+            SaleBuilder::new(Vec::new(), Date::new(3, 2, 2025).unwrap()).bundles(vec![0]).call(),
+            Error::<Test>::InsufficientStock
+        );
+        assert_eq!(Products::<Test>::get(console).unwrap().stock, 10);
+
+        assert_noop!(
+            SaleBuilder::new(Vec::new(), Date::new(3, 2, 2025).unwrap()).bundles(vec![7]).call(),
+            Error::<Test>::BundleNotFound
+        );
+    });
+}
+
+#[test]
+fn it_accrues_the_seller_commission() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 100, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        // 500 pontos-base sobre 1000 = 50
+        CommissionBasisPoints::set(500);
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 10, discount_percent: None }], date.clone());
+        assert_eq!(Sales::<Test>::get(code).unwrap().commission, 50);
+        System::assert_has_event(Event::CommissionAccrued(b"Test Seller".to_vec(), 50).into());
+
+        // Sem comissão configurada, nada é acumulado
+        CommissionBasisPoints::set(0);
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 10, discount_percent: None }], date);
+        assert_eq!(Sales::<Test>::get(code).unwrap().commission, 0);
+        assert_eq!(count_events(Event::CommissionAccrued(b"Test Seller".to_vec(), 0)), 0);
+    });
+}
+
+#[test]
+fn it_lists_the_sales_of_a_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let other = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        let first = register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], date.clone());
+        register_test_sale(vec![ItemSale { product_id: other, amount: 1, discount_percent: None }], date.clone());
+        let second = register_test_sale(
+            vec![
+                ItemSale { product_id: id, amount: 3, discount_percent: None },
+                ItemSale { product_id: id, amount: 1, discount_percent: None },
+            ],
+            date
+        );
+
+        assert_ok!(Pallet::<Test>::get_product_sales(RuntimeOrigin::signed(1), id, 10));
+        System::assert_last_event(Event::ProductSalesListed(id, vec![(first, 2), (second, 4)]).into());
+
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), first));
+        assert_ok!(Pallet::<Test>::get_product_sales(RuntimeOrigin::signed(1), id, 10));
+        System::assert_last_event(Event::ProductSalesListed(id, vec![(second, 4)]).into());
+    });
+}
+
+#[test]
+fn it_adds_a_product_and_sells_it_atomically() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Pallet::<Test>::add_product_and_sell(
+            RuntimeOrigin::signed(1),
+            b"Opening Product".to_vec(),
+            10,
+            25,
+            2,
+            Date::new(1, 1, 2023).unwrap(),
+            Category::Misc,
+            None,
+            None,
+            None,
+            None,
+            Vec::new(),
+            4,
+            b"Test Seller".to_vec(),
+            PaymentMethod::Pix,
+            Date::new(3, 2, 2025).unwrap()
+        ));
+
+        System::assert_has_event(Event::ProductAdded(0).into());
+        System::assert_last_event(Event::SaleRegistered(0).into());
+        assert_eq!(Products::<Test>::get(0).unwrap().stock, 6);
+        assert_eq!(Sales::<Test>::get(0).unwrap().value, 100);
+    });
+}
+
+#[test]
+fn it_rolls_back_the_product_when_the_initial_sale_fails() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Pallet::<Test>::add_product_and_sell(
+                RuntimeOrigin::signed(1),
+                b"Opening Product".to_vec(),
+                3,
+                25,
+                2,
+                Date::new(1, 1, 2023).unwrap(),
+                Category::Misc,
+                None,
+                None,
+                None,
+                None,
+                Vec::new(),
+                4,
+                b"Test Seller".to_vec(),
+                PaymentMethod::Pix,
+                Date::new(3, 2, 2025).unwrap()
+            ),
+            Error::<Test>::InsufficientStock
+        );
+
+        assert!(Products::<Test>::get(0).is_none());
+        assert_eq!(Pallet::<Test>::next_product_id(), 0);
+    });
+}
+
+#[test]
+fn it_rejects_dates_outside_the_configured_years() {
+    new_test_ext().execute_with(|| {
+        let add_with_year = |year: u64| {
+            ProductBuilder::new(10, 10, 5, Category::Misc).restock_date(Date { day: 1, month: 1, year }).call()
+        };
+
+        assert_ok!(add_with_year(2000));
+        assert_ok!(add_with_year(2100));
+        assert_noop!(add_with_year(1999), Error::<Test>::InvalidDate);
+        assert_noop!(add_with_year(2101), Error::<Test>::InvalidDate);
+        assert_noop!(add_with_year(9_999_999), Error::<Test>::InvalidDate);
+    });
+}
+
+#[test]
+fn it_exports_a_sorted_and_bounded_snapshot() {
+    new_test_ext().execute_with(|| {
+        let ids: Vec<u64> = (0..4).map(|i| add_test_product(100, 10 + i, 5, Category::Misc)).collect();
+        let date = Date::new(3, 2, 2025).unwrap();
+        let codes: Vec<u64> = ids.iter()
+            .map(|id| register_test_sale(vec![ItemSale { product_id: *id, amount: 1, discount_percent: None }], date.clone()))
+            .collect();
+
+        let (products, sales) = Pallet::<Test>::export_snapshot();
+
+        // Apenas os três primeiros registros de cada tipo, em ordem
+        let expected_products: Vec<ProductOf<Test>> = ids[..3].iter().map(|id| Products::<Test>::get(id).unwrap()).collect();
+        let expected_sales: Vec<Sale> = codes[..3].iter().map(|code| Sales::<Test>::get(code).unwrap()).collect();
+        assert_eq!(products, expected_products);
+        assert_eq!(sales, expected_sales);
+    });
+}
+
+#[test]
+fn it_imports_an_exported_snapshot() {
+    let (products, sales) = new_test_ext().execute_with(|| {
+        add_test_product(100, 10, 5, Category::Misc);
+        let id = add_test_product(100, 20, 5, Category::Food);
+        register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        Pallet::<Test>::export_snapshot()
+    });
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(Pallet::<Test>::import_snapshot(RuntimeOrigin::root(), 3, products.clone(), sales.clone()));
+        System::assert_last_event(Event::SnapshotImported(2, 1).into());
+
+        assert_eq!(Pallet::<Test>::export_snapshot(), (products, sales));
+        assert_eq!(Pallet::<Test>::next_product_id(), 2);
+        assert_eq!(Pallet::<Test>::next_sale_code(), 1);
+        assert_eq!(Pallet::<Test>::daily_revenue(Date::new(3, 2, 2025).unwrap()), 40);
+
+        // Importar os mesmos registros novamente repetiria IDs
+        assert_noop!(
+            Pallet::<Test>::import_snapshot(RuntimeOrigin::root(), 3, Vec::new(), Pallet::<Test>::export_snapshot().1),
+            Error::<Test>::InvalidSnapshot
+        );
+
+        // Os produtos importados pertencem à conta informada
+        for id in [0, 1] {
+            assert_eq!(ProductOwners::<Test>::get(id), Some(3));
+            assert!(OwnerProducts::<Test>::contains_key(3, id));
+        }
+    });
+}
+
+#[test]
+fn it_refunds_imported_sales_without_touching_other_sales() {
+    let (products, sales) = new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 20, 5, Category::Food);
+        register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        Pallet::<Test>::export_snapshot()
+    });
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(Pallet::<Test>::import_snapshot(RuntimeOrigin::root(), 1, products, sales));
+        assert_eq!(Pallet::<Test>::sale_tx_count(0), 1);
+        assert_eq!(Pallet::<Test>::units_sold(0), 2);
+        assert_eq!(Pallet::<Test>::category_revenue(&Category::Food), 40);
+
+        // Uma venda registrada após a importação
+        let code = register_test_sale(vec![ItemSale { product_id: 0, amount: 3, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+
+        // O estorno da venda importada retira apenas os seus próprios valores
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), 0));
+        assert_eq!(Pallet::<Test>::sale_tx_count(0), 1);
+        assert_eq!(Pallet::<Test>::units_sold(0), 3);
+        assert_eq!(Pallet::<Test>::category_revenue(&Category::Food), 60);
+        assert_eq!(ProductSales::<Test>::iter_prefix(0).collect::<Vec<_>>(), vec![(code, 3)]);
+    });
+}
+
+#[test]
+fn it_takes_imported_ids_out_of_the_free_list() {
+    let (products, _) = new_test_ext().execute_with(|| {
+        add_test_product(10, 50, 5, Category::Misc);
+        add_test_product(10, 50, 5, Category::Misc);
+        Pallet::<Test>::export_snapshot()
+    });
+
+    new_test_ext().execute_with(|| {
+        RecycleIds::set(true);
+        add_test_product(10, 50, 5, Category::Misc);
+        let freed = add_test_product(10, 50, 5, Category::Misc);
+        add_test_product(10, 50, 5, Category::Misc);
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), freed));
+        assert_eq!(Pallet::<Test>::free_product_ids().into_inner(), vec![freed]);
+
+        // O produto importado ocupa o ID livre, que não é mais reaproveitado
+        assert_ok!(Pallet::<Test>::import_snapshot(RuntimeOrigin::root(), 1, vec![products[1].clone()], Vec::new()));
+        assert!(Pallet::<Test>::free_product_ids().is_empty());
+        assert_eq!(add_test_product(10, 50, 5, Category::Misc), 3);
+        assert!(Products::<Test>::contains_key(freed));
+    });
+}
+
+#[test]
+fn it_refuses_to_import_while_paused() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Pallet::<Test>::set_paused(RuntimeOrigin::root(), true));
+        assert_noop!(Pallet::<Test>::import_snapshot(RuntimeOrigin::root(), 1, Vec::new(), Vec::new()), Error::<Test>::Paused);
+    });
+}
+
+#[test]
+fn it_validates_imported_records_like_new_ones() {
+    let (products, sales) = new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 20, 5, Category::Food);
+        register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        Pallet::<Test>::export_snapshot()
+    });
+
+    new_test_ext().execute_with(|| {
+        // Vendas de produtos inexistentes
+        assert_noop!(
+            Pallet::<Test>::import_snapshot(RuntimeOrigin::root(), 1, Vec::new(), sales.clone()),
+            Error::<Test>::InvalidSnapshot
+        );
+
+        // Estoque fracionário de um produto vendido por peça
+        StockUnit::set(1_000);
+        assert_noop!(
+            Pallet::<Test>::import_snapshot(RuntimeOrigin::root(), 1, products.clone(), sales.clone()),
+            Error::<Test>::FractionalQuantity
+        );
+        StockUnit::set(1);
+
+        // Limite de estoque da categoria
+        assert_ok!(Pallet::<Test>::set_category_stock_cap(RuntimeOrigin::root(), Category::Food, Some(50)));
+        assert_noop!(
+            Pallet::<Test>::import_snapshot(RuntimeOrigin::root(), 1, products, sales),
+            Error::<Test>::CategoryCapExceeded
+        );
+    });
+}
+
+#[test]
+fn it_rejects_a_snapshot_with_an_invalid_date() {
+    let (mut products, sales) = new_test_ext().execute_with(|| {
+        add_test_product(100, 10, 5, Category::Misc);
+        add_test_product(100, 20, 5, Category::Food);
+        Pallet::<Test>::export_snapshot()
+    });
+    products[1].restock_date = Date { day: 32, month: 1, year: 2025 };
+
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Pallet::<Test>::import_snapshot(RuntimeOrigin::root(), 1, products, sales),
+            Error::<Test>::InvalidDate
+        );
+        assert!(Products::<Test>::get(0).is_none());
+    });
+}
+
+#[test]
+fn it_counts_sale_transactions_per_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], date.clone());
+        register_test_sale(
+            vec![
+                ItemSale { product_id: id, amount: 5, discount_percent: None },
+                ItemSale { product_id: id, amount: 1, discount_percent: None },
+            ],
+            date
+        );
+
+        // Duas vendas, oito unidades
+        assert_eq!(Pallet::<Test>::sale_tx_count(id), 2);
+        assert_eq!(ProductSales::<Test>::iter_prefix_values(id).sum::<u64>(), 8);
+    });
+}
+
+#[test]
+fn it_records_backorders_when_oversell_is_allowed() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(3, 10, 1, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        AllowOversell::set(true);
+        register_test_sale(vec![ItemSale { product_id: id, amount: 5, discount_percent: None }], date.clone());
+        System::assert_has_event(Event::Backordered(id, 2).into());
+
+        register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], date);
+        assert_eq!(Products::<Test>::get(id).unwrap().stock, 0);
+        assert_eq!(Pallet::<Test>::backorders(id), 3);
+    });
+}
+
+#[test]
+fn it_rejects_oversell_by_default() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(3, 10, 1, Category::Misc);
+
+        assert_err!(
+            SaleBuilder::new(vec![ItemSale { product_id: id, amount: 5, discount_percent: None }], Date::new(3, 2, 2025).unwrap()).call(),
+            Error::<Test>::InsufficientStock
+        );
+        assert_eq!(Pallet::<Test>::backorders(id), 0);
+    });
+}
+
+#[test]
+fn it_computes_tax_per_product_tax_category() {
+    new_test_ext().execute_with(|| {
+        let food = add_test_product(100, 200, 5, Category::Food);
+        let gadget = add_test_product(100, 300, 5, Category::Electronic);
+        let untaxed = add_test_product(100, 100, 5, Category::Misc);
+
+        assert_ok!(Pallet::<Test>::set_tax_rate(RuntimeOrigin::root(), 1, Some(500)));
+        assert_ok!(Pallet::<Test>::set_tax_rate(RuntimeOrigin::root(), 2, Some(2_000)));
+        assert_ok!(Pallet::<Test>::set_product_tax_category(RuntimeOrigin::signed(1), food, 1));
+        assert_ok!(Pallet::<Test>::set_product_tax_category(RuntimeOrigin::signed(1), gadget, 2));
+        // Categoria 7 sem alíquota cadastrada: usa a alíquota padrão de 10%
+        assert_ok!(Pallet::<Test>::set_product_tax_category(RuntimeOrigin::signed(1), untaxed, 7));
+
+        let code = register_test_sale(
+            vec![
+                ItemSale { product_id: food, amount: 1, discount_percent: None },
+                ItemSale { product_id: gadget, amount: 1, discount_percent: None },
+                ItemSale { product_id: untaxed, amount: 1, discount_percent: None },
+            ],
+            Date::new(3, 2, 2025).unwrap()
+        );
+
+        // 5% de 200 + 20% de 300 + 10% de 100
+        assert_eq!(Sales::<Test>::get(code).unwrap().tax, 10 + 60 + 10);
+    });
+}
+
+#[test]
+fn it_rejects_identical_sales_in_the_same_block_with_hashed_codes() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+        let items = vec![ItemSale { product_id: id, amount: 1, discount_percent: None }];
+
+        HashedSaleCodes::set(true);
+        let code = register_test_sale(items.clone(), date.clone());
+        assert!(Sales::<Test>::contains_key(code));
+        assert_eq!(Pallet::<Test>::next_sale_code(), 0);
+
+        assert_noop!(
+            SaleBuilder::new(items.clone(), date.clone()).call(),
+            Error::<Test>::SaleCodeCollision
+        );
+
+        // Em outro bloco o mesmo conteúdo gera um código diferente
+        run_to_block(2);
+        let other = register_test_sale(items, date);
+        assert_ne!(other, code);
+    });
+}
+
+#[test]
+fn it_blocks_mutating_calls_while_paused() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+
+        assert_ok!(Pallet::<Test>::set_paused(RuntimeOrigin::root(), true));
+        System::assert_last_event(Event::PausedSet(true).into());
+
+        assert_noop!(
+            ProductBuilder::new(10, 10, 5, Category::Misc).call(),
+            Error::<Test>::Paused
+        );
+        assert_noop!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), id), Error::<Test>::Paused);
+
+        // Alterações administrativas em massa também são bloqueadas
+        assert_noop!(Pallet::<Test>::adjust_all_prices(RuntimeOrigin::root(), 5), Error::<Test>::Paused);
+        assert_noop!(Pallet::<Test>::archive_below_stock(RuntimeOrigin::root(), 1_000), Error::<Test>::Paused);
+        assert_noop!(Pallet::<Test>::reassign_category(RuntimeOrigin::root(), Category::Misc, Category::Food), Error::<Test>::Paused);
+
+        // Consultas continuam permitidas
+        assert_ok!(Pallet::<Test>::get_product(RuntimeOrigin::signed(1), id));
+
+        assert_ok!(Pallet::<Test>::set_paused(RuntimeOrigin::root(), false));
+        add_test_product(10, 10, 5, Category::Misc);
+    });
+}
+
+#[test]
+fn it_flags_restock_sla_breaches_once() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(2, 10, 5, Category::Misc);
+        assert_eq!(Pallet::<Test>::low_stock_since(id), Some(1));
+
+        // Cinco blocos abaixo do limite ainda estão dentro do prazo
+        run_to_block(6);
+        assert_eq!(count_events(Event::RestockSlaBreached(id, 6)), 0);
+
+        run_to_block(10);
+        assert_eq!(count_events(Event::RestockSlaBreached(id, 6)), 1);
+        assert_eq!(System::events().iter().filter(|record| matches!(record.event, RuntimeEvent::TemplateModule(Event::RestockSlaBreached(..)))).count(), 1);
+
+        // A reposição encerra o acompanhamento
+        assert_ok!(ProductUpdate::new(id).stock(20).call());
+        assert_eq!(Pallet::<Test>::low_stock_since(id), None);
+    });
+}
+
+#[test]
+fn it_checks_restock_slas_in_bounded_batches() {
+    new_test_ext().execute_with(|| {
+        let first = add_test_product(2, 10, 5, Category::Misc);
+        let second = add_test_product(2, 10, 5, Category::Misc);
+        let third = add_test_product(2, 10, 5, Category::Misc);
+        let restocked = add_test_product(2, 10, 5, Category::Misc);
+        assert_ok!(ProductUpdate::new(restocked).stock(20).call());
+
+        // No máximo dois alertas verificados por bloco; o excedente fica para o bloco seguinte
+        run_to_block(7);
+        assert_eq!(count_events(Event::RestockSlaBreached(first, 6)), 1);
+        assert_eq!(count_events(Event::RestockSlaBreached(second, 6)), 1);
+        assert_eq!(RestockSlaQueueBounds::<Test>::get(), (2, 4));
+
+        // O alerta encerrado apenas sai da fila
+        run_to_block(8);
+        assert_eq!(count_events(Event::RestockSlaBreached(third, 7)), 1);
+        assert_eq!(RestockSlaQueueBounds::<Test>::get(), (4, 4));
+        assert_eq!(System::events().iter().filter(|record| matches!(record.event, RuntimeEvent::TemplateModule(Event::RestockSlaBreached(..)))).count(), 3);
+    });
+}
+
+#[test]
+fn it_tracks_revenue_per_category() {
+    new_test_ext().execute_with(|| {
+        let food = add_test_product(100, 10, 5, Category::Food);
+        let shirt = add_test_product(100, 40, 5, Category::Clothing);
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        register_test_sale(
+            vec![
+                ItemSale { product_id: food, amount: 3, discount_percent: None },
+                ItemSale { product_id: shirt, amount: 1, discount_percent: None },
+            ],
+            date.clone()
+        );
+        let code = register_test_sale(vec![ItemSale { product_id: shirt, amount: 2, discount_percent: None }], date);
+
+        assert_eq!(Pallet::<Test>::category_revenue(Category::Food), 30);
+        assert_eq!(Pallet::<Test>::category_revenue(Category::Clothing), 120);
+
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), code));
+        assert_ok!(Pallet::<Test>::get_category_revenue(RuntimeOrigin::signed(1), Category::Clothing));
+        System::assert_last_event(Event::CategoryRevenueGotten(Category::Clothing, 40).into());
+    });
+}
+
+#[test]
+fn it_clamps_listing_limits_to_the_page_size() {
+    new_test_ext().execute_with(|| {
+        EmitListingPayloads::set(false);
+        for _ in 0..12 {
+            add_test_product(1, 10, 0, Category::Misc);
+        }
+
+        // Limite acima do máximo é reduzido a MaxPageSize
+        assert_ok!(Pallet::<Test>::list_products_below_stock(RuntimeOrigin::signed(1), 5, 50));
+        System::assert_last_event(Event::ProductsBelowStockCount(10).into());
+
+        // Limite zero usa DefaultPageSize
+        assert_ok!(Pallet::<Test>::list_products_below_stock(RuntimeOrigin::signed(1), 5, 0));
+        System::assert_last_event(Event::ProductsBelowStockCount(3).into());
+    });
+}
+
+#[test]
+fn it_computes_restock_thresholds_in_days_of_cover() {
+    new_test_ext().execute_with(|| {
+        EmitListingPayloads::set(false);
+        let id = add_test_product(50, 10, 4, Category::Misc);
+        register_test_sale(vec![ItemSale { product_id: id, amount: 20, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+
+        // 20 unidades em 20 blocos (dois dias): 10 unidades por dia
+        run_to_block(21);
+
+        // Em unidades, 30 em estoque está acima do limite de 4
+        let product = Products::<Test>::get(id).unwrap();
+        assert_eq!(Pallet::<Test>::effective_restock_threshold(id, &product), 4);
+        assert_ok!(Pallet::<Test>::list_products_to_restock(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsToRestockCount(0).into());
+
+        // Em dias de cobertura, 4 dias equivalem a 40 unidades
+        assert_ok!(Pallet::<Test>::set_threshold_mode(RuntimeOrigin::signed(1), id, ThresholdMode::DaysOfCover));
+        let product = Products::<Test>::get(id).unwrap();
+        assert_eq!(Pallet::<Test>::effective_restock_threshold(id, &product), 40);
+        assert_ok!(Pallet::<Test>::list_products_to_restock(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsToRestockCount(1).into());
+    });
+}
+
+#[test]
+fn it_reads_days_of_cover_from_the_reorder_point() {
+    new_test_ext().execute_with(|| {
+        EmitListingPayloads::set(false);
+        let id = add_test_product(50, 10, 4, Category::Misc);
+        register_test_sale(vec![ItemSale { product_id: id, amount: 20, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        run_to_block(21);
+
+        assert_ok!(Pallet::<Test>::set_threshold_mode(RuntimeOrigin::signed(1), id, ThresholdMode::DaysOfCover));
+        assert_ok!(Pallet::<Test>::set_reorder_point(RuntimeOrigin::signed(1), id, 2));
+
+        // 2 dias a 10 unidades por dia; a quantidade reposta não entra no cálculo
+        let product = Products::<Test>::get(id).unwrap();
+        assert_eq!(product.amount_to_restock, 4);
+        assert_eq!(Pallet::<Test>::effective_restock_threshold(id, &product), 20);
+        assert_ok!(Pallet::<Test>::list_products_to_restock(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsToRestockCount(0).into());
+    });
+}
+
+#[test]
+fn it_adjusts_all_prices_by_a_delta() {
+    new_test_ext().execute_with(|| {
+        let cheap = add_test_product(10, 5, 1, Category::Misc);
+        let pricey = add_test_product(10, 100, 1, Category::Misc);
+
+        assert_ok!(Pallet::<Test>::adjust_all_prices(RuntimeOrigin::root(), 10));
+        System::assert_last_event(Event::AllPricesAdjusted(2, 10).into());
+        assert_eq!(Products::<Test>::get(cheap).unwrap().price, 15);
+        assert_eq!(Products::<Test>::get(pricey).unwrap().price, 110);
+
+        // O produto barato ficaria com preço negativo: nada é alterado
+        assert_noop!(Pallet::<Test>::adjust_all_prices(RuntimeOrigin::root(), -20), Error::<Test>::Overflow);
+        assert_eq!(Products::<Test>::get(pricey).unwrap().price, 110);
+    });
+}
+
+#[test]
+fn it_keeps_adjusted_prices_above_the_price_floor() {
+    new_test_ext().execute_with(|| {
+        let floored = add_test_product(10, 100, 1, Category::Misc);
+        let other = add_test_product(10, 100, 1, Category::Misc);
+        assert_ok!(Pallet::<Test>::set_price_floor(RuntimeOrigin::signed(1), floored, Some(95)));
+
+        // O produto com piso ficaria abaixo dele: nada é alterado
+        assert_noop!(Pallet::<Test>::adjust_all_prices(RuntimeOrigin::root(), -10), Error::<Test>::InvalidPriceFloor);
+
+        assert_ok!(Pallet::<Test>::adjust_all_prices(RuntimeOrigin::root(), -5));
+        assert_eq!(Products::<Test>::get(floored).unwrap().price, 95);
+        assert_eq!(Products::<Test>::get(other).unwrap().price, 95);
+    });
+}
+
+#[test]
+fn it_keeps_adjusted_prices_above_the_member_price() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(10, 100, 1, Category::Misc);
+        assert_ok!(Pallet::<Test>::set_member_price(RuntimeOrigin::signed(1), id, Some(90)));
+
+        // O preço padrão ficaria abaixo do preço para membros: nada é alterado
+        assert_noop!(Pallet::<Test>::adjust_all_prices(RuntimeOrigin::root(), -20), Error::<Test>::InvalidMemberPrice);
+
+        assert_ok!(Pallet::<Test>::adjust_all_prices(RuntimeOrigin::root(), -10));
+        assert_eq!(Products::<Test>::get(id).unwrap().price, 90);
+    });
+}
+
+#[test]
+fn it_keeps_the_minimum_margin_when_adjusting_prices() {
+    new_test_ext().execute_with(|| {
+        MinMarginBasisPoints::set(2_000);
+        let id = add_test_product(10, 100, 1, Category::Misc);
+        assert_ok!(Pallet::<Test>::set_cost_price(RuntimeOrigin::signed(1), id, Some(70)));
+
+        // A 80, a margem seria de 12,5%, abaixo do mínimo de 20%: nada é alterado
+        assert_noop!(Pallet::<Test>::adjust_all_prices(RuntimeOrigin::root(), -20), Error::<Test>::MarginTooLow);
+
+        // A 90, a margem é de 22%
+        assert_ok!(Pallet::<Test>::adjust_all_prices(RuntimeOrigin::root(), -10));
+        assert_eq!(Products::<Test>::get(id).unwrap().price, 90);
+    });
+}
+
+#[test]
+fn it_rounds_discounted_prices_to_the_increment() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 100, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+        // 3% de desconto sobre 100 resulta em 97
+        let discounted = || vec![ItemSale { product_id: id, amount: 1, discount_percent: Some(3) }];
+
+        let code = register_test_sale(discounted(), date.clone());
+        assert_eq!(Sales::<Test>::get(code).unwrap().value, 97);
+
+        PriceIncrement::set(5);
+        let code = register_test_sale(discounted(), date.clone());
+        assert_eq!(Sales::<Test>::get(code).unwrap().value, 95);
+
+        Rounding::set(RoundingMode::Up);
+        let code = register_test_sale(discounted(), date);
+        assert_eq!(Sales::<Test>::get(code).unwrap().value, 100);
+    });
+}
+
+#[test]
+fn it_lists_dead_stock() {
+    new_test_ext().execute_with(|| {
+        let sold = add_test_product(100, 10, 5, Category::Misc);
+        let unsold = add_test_product(100, 10, 5, Category::Misc);
+        let stale = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        register_test_sale(vec![ItemSale { product_id: stale, amount: 1, discount_percent: None }], date.clone());
+        run_to_block(10);
+        register_test_sale(vec![ItemSale { product_id: sold, amount: 1, discount_percent: None }], date);
+        run_to_block(12);
+
+        assert_ok!(Pallet::<Test>::list_dead_stock(RuntimeOrigin::signed(1), 5, 10));
+        System::assert_last_event(Event::DeadStockListed(vec![unsold, stale]).into());
+    });
+}
+
+#[test]
+fn it_restricts_catalog_changes_to_staff() {
+    new_test_ext().execute_with(|| {
+        // A conta 2 é apenas cliente
+        assert_noop!(
+            ProductBuilder::new(10, 10, 5, Category::Misc).origin(RuntimeOrigin::signed(2)).call(),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        // A conta 1 é funcionária e o cliente pode comprar
+        let id = add_test_product(10, 10, 5, Category::Misc);
+        assert_ok!(SaleBuilder::new(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap())
+            .origin(RuntimeOrigin::signed(2))
+            .call());
+        assert_eq!(Pallet::<Test>::buyer_spend(2), 10);
+    });
+}
+
+#[test]
+fn it_pages_products_with_storage_cursors() {
+    new_test_ext().execute_with(|| {
+        for _ in 0..100 {
+            add_test_product(10, 10, 5, Category::Misc);
+        }
+
+        let mut seen: Vec<u64> = Vec::new();
+        let (page, mut cursor) = Pallet::<Test>::products_page(None, 10);
+        seen.extend(page.into_iter().map(|(id, _)| id));
+
+        // Inserção entre páginas não deve causar omissões nem repetições
+        let inserted = add_test_product(10, 10, 5, Category::Misc);
+
+        while let Some(next) = cursor {
+            let (page, following) = Pallet::<Test>::products_page(Some(next), 10);
+            assert!(page.len() <= 10);
+            seen.extend(page.into_iter().map(|(id, _)| id));
+            cursor = following;
+        }
+
+        seen.retain(|id| *id != inserted);
+        seen.sort();
+        assert_eq!(seen, (0..100).collect::<Vec<u64>>());
+    });
+}
+
+#[test]
+fn it_enforces_order_quantity_limits() {
+    new_test_ext().execute_with(|| {
+        let add_with_limits = |min: u64, max: u64| {
+            ProductBuilder::new(500, 10, 5, Category::Food).name(b"Six Pack".to_vec()).min_order_qty(min).max_order_qty(max).call()
+        };
+
+        assert_noop!(add_with_limits(10, 6), Error::<Test>::InvalidOrderLimits);
+        assert_ok!(add_with_limits(6, 100));
+        let id = last_added_product();
+
+        let sell = |amount: u64| {
+            SaleBuilder::new(vec![ItemSale { product_id: id, amount, discount_percent: None }], Date::new(3, 2, 2025).unwrap()).call()
+        };
+
+        assert_noop!(sell(5), Error::<Test>::QuantityBelowMinimum);
+        assert_noop!(sell(101), Error::<Test>::QuantityAboveMaximum);
+        assert_ok!(sell(6));
+        assert_ok!(sell(100));
+        assert_eq!(Products::<Test>::get(id).unwrap().stock, 394);
+    });
+}
+
+#[test]
+fn it_charges_and_burns_the_sale_fee() {
+    new_test_ext().execute_with(|| {
+        use frame_support::traits::fungible::{Inspect, Mutate};
+
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        assert_ok!(<Balances as Mutate<u64>>::mint_into(&2, 100));
+        SaleFee::set(5);
+
+        let sell = |buyer: u64| {
+            SaleBuilder::new(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap())
+                .origin(RuntimeOrigin::signed(buyer))
+                .call()
+        };
+
+        assert_ok!(sell(2));
+        System::assert_has_event(Event::SaleFeeCharged(5).into());
+        assert_eq!(Balances::balance(&2), 95);
+        assert_eq!(Balances::total_issuance(), 95);
+
+        // A conta 3 não tem saldo para a taxa
+        assert_noop!(sell(3), Error::<Test>::InsufficientBalanceForFee);
+    });
+}
+
+#[test]
+fn it_finds_the_highest_value_product() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Pallet::<Test>::highest_value_product(), None);
+
+        add_test_product(10, 50, 1, Category::Misc);
+        let winner = add_test_product(20, 40, 1, Category::Food);
+        add_test_product(100, 5, 1, Category::Clothing);
+        // Valor em estoque estoura e é ignorado
+        add_test_product(u64::MAX, 2, 1, Category::Misc);
+
+        assert_eq!(Pallet::<Test>::highest_value_product(), Some((winner, 800)));
+    });
+}
+
+#[test]
+fn it_ranks_the_highest_value_product_in_whole_units() {
+    new_test_ext().execute_with(|| {
+        // Estoque em milésimos: o produto mais barato por unidade tem mais unidades inteiras
+        StockUnit::set(1_000);
+        add_test_product(1_500, 100, 1_000, Category::Misc);
+        let winner = add_test_product(20_000, 10, 1_000, Category::Food);
+        // Estoura u64 mesmo depois da escala e é ignorado
+        add_test_product(u64::MAX, u64::MAX, 1_000, Category::Misc);
+
+        assert_eq!(Pallet::<Test>::highest_value_product(), Some((winner, 200)));
+    });
+}
+
+#[test]
+fn it_signals_the_first_product_of_a_category() {
+    new_test_ext().execute_with(|| {
+        let first = add_test_product(10, 10, 1, Category::Food);
+        let second = add_test_product(10, 10, 1, Category::Food);
+
+        assert_eq!(count_events(Event::NewCategoryProduct(Category::Food, first)), 1);
+        assert_eq!(count_events(Event::NewCategoryProduct(Category::Food, second)), 0);
+    });
+}
+
+#[test]
+fn it_sells_fractional_amounts_of_weighted_goods() {
+    new_test_ext().execute_with(|| {
+        // Estoque em gramas: 5 kg a 200 por kg
+        StockUnit::set(1_000);
+        let id = add_test_product(5_000, 200, 1_000, Category::Food);
+        assert_ok!(ProductUpdate::new(id).unit(StockUnitLabel::Kilogram).call());
+
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1_500, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+
+        assert_eq!(Products::<Test>::get(id).unwrap().stock, 3_500);
+        assert_eq!(Sales::<Test>::get(code).unwrap().value, 300);
+
+        assert_noop!(
+            SaleBuilder::new(vec![ItemSale { product_id: id, amount: 0, discount_percent: None }], Date::new(3, 2, 2025).unwrap()).call(),
+            Error::<Test>::InvalidQuantity
+        );
+    });
+}
+
+#[test]
+fn it_archives_products_below_a_stock_level_in_pages() {
+    new_test_ext().execute_with(|| {
+        let ids: Vec<u64> = (0..15).map(|i| add_test_product(i % 5, 10, 0, Category::Misc)).collect();
+
+        // Quinze produtos exigem duas chamadas com páginas de dez
+        assert_ok!(Pallet::<Test>::archive_below_stock(RuntimeOrigin::root(), 1));
+        assert!(Pallet::<Test>::archive_cursor().is_some());
+        assert_ok!(Pallet::<Test>::archive_below_stock(RuntimeOrigin::root(), 1));
+        assert!(Pallet::<Test>::archive_cursor().is_none());
+
+        let archived: u32 = System::events()
+            .into_iter()
+            .filter_map(|record| match record.event {
+                RuntimeEvent::TemplateModule(Event::BulkArchived(count)) => Some(count),
+                _ => None,
+            })
+            .sum();
+        assert_eq!(archived, 6);
+
+        for (i, id) in ids.into_iter().enumerate() {
+            let below = (i % 5) <= 1;
+            assert_eq!(Pallet::<Test>::archived_products(id).is_some(), below);
+            assert_eq!(Products::<Test>::contains_key(id), !below);
+        }
+    });
+}
+
+#[test]
+fn it_drops_the_owner_of_archived_products() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(1, 10, 0, Category::Misc);
+        assert!(OwnerProducts::<Test>::contains_key(1, id));
+
+        assert_ok!(Pallet::<Test>::archive_below_stock(RuntimeOrigin::root(), 1));
+
+        assert!(!ProductOwners::<Test>::contains_key(id));
+        assert!(!OwnerProducts::<Test>::contains_key(1, id));
+    });
+}
+
+#[test]
+fn it_keeps_the_catalog_version_when_nothing_is_archived() {
+    new_test_ext().execute_with(|| {
+        add_test_product(100, 10, 0, Category::Misc);
+        let version = Pallet::<Test>::catalog_version();
+
+        assert_ok!(Pallet::<Test>::archive_below_stock(RuntimeOrigin::root(), 1));
+        System::assert_last_event(Event::BulkArchived(0).into());
+        assert_eq!(Pallet::<Test>::catalog_version(), version);
+
+        assert_ok!(Pallet::<Test>::archive_below_stock(RuntimeOrigin::root(), 100));
+        System::assert_last_event(Event::BulkArchived(1).into());
+        assert_eq!(Pallet::<Test>::catalog_version(), version + 1);
+    });
+}
+
+#[test]
+fn it_rejects_restock_dates_in_the_past_when_enforced() {
+    new_test_ext().execute_with(|| {
+        // 03/02/2025 00:00 UTC
+        Now::set(1_738_540_800);
+        assert_eq!(Pallet::<Test>::today(), Date::new(3, 2, 2025).unwrap());
+
+        let add_with_restock_date = |date: Date| {
+            ProductBuilder::new(10, 10, 5, Category::Misc).restock_date(date).call()
+        };
+
+        // Sem a verificação, datas passadas continuam aceitas
+        assert_ok!(add_with_restock_date(Date::new(2, 2, 2025).unwrap()));
+
+        EnforceFutureRestock::set(true);
+        assert_noop!(add_with_restock_date(Date::new(2, 2, 2025).unwrap()), Error::<Test>::RestockDateInPast);
+        assert_noop!(add_with_restock_date(Date::new(31, 12, 2024).unwrap()), Error::<Test>::RestockDateInPast);
+        assert_ok!(add_with_restock_date(Date::new(3, 2, 2025).unwrap()));
+        assert_ok!(add_with_restock_date(Date::new(1, 1, 2026).unwrap()));
+    });
+}
+
+#[test]
+fn it_lists_only_the_callers_products() {
+    new_test_ext().execute_with(|| {
+        let first = add_test_product(10, 10, 1, Category::Misc);
+        let second = add_test_product(10, 10, 1, Category::Misc);
+        let other = ProductBuilder::new(10, 10, 1, Category::Misc).origin(RuntimeOrigin::signed(3)).name(b"Other Product".to_vec()).add();
+
+        assert_ok!(Pallet::<Test>::list_my_products(RuntimeOrigin::signed(1), 10));
+        System::assert_last_event(Event::OwnerProductsListed(1, vec![first, second]).into());
+        assert_ok!(Pallet::<Test>::list_my_products(RuntimeOrigin::signed(3), 10));
+        System::assert_last_event(Event::OwnerProductsListed(3, vec![other]).into());
+
+        // Transferência e remoção mantêm o índice consistente
+        assert_noop!(Pallet::<Test>::transfer_product(RuntimeOrigin::signed(3), first, 3), Error::<Test>::NotProductOwner);
+        assert_ok!(Pallet::<Test>::transfer_product(RuntimeOrigin::signed(1), first, 3));
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), second));
+
+        assert_ok!(Pallet::<Test>::list_my_products(RuntimeOrigin::signed(1), 10));
+        System::assert_last_event(Event::OwnerProductsListed(1, vec![]).into());
+        assert_ok!(Pallet::<Test>::list_my_products(RuntimeOrigin::signed(3), 10));
+        System::assert_last_event(Event::OwnerProductsListed(3, vec![first, other]).into());
+    });
+}
+
+#[test]
+fn it_fails_when_the_sale_total_overflows() {
+    new_test_ext().execute_with(|| {
+        let cheap = add_test_product(100, 10, 1, Category::Misc);
+        let luxury = add_test_product(100, u64::MAX / 2, 1, Category::Misc);
+
+        let items = vec![
+            ItemSale { product_id: cheap, amount: 3, discount_percent: None },
+            ItemSale { product_id: luxury, amount: 3, discount_percent: None },
+        ];
+        assert_noop!(
+            SaleBuilder::new(items, Date::new(3, 2, 2025).unwrap()).call(),
+            Error::<Test>::Overflow
+        );
+    });
+}
+
+#[test]
+fn it_bumps_versions_on_mutations_only() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 1, Category::Misc);
+        assert_eq!(Pallet::<Test>::catalog_version(), 1);
+
+        assert_ok!(ProductUpdate::new(id).price(20).call());
+        assert_eq!(Pallet::<Test>::catalog_version(), 2);
+
+        // Consultas não alteram as versões
+        assert_ok!(Pallet::<Test>::get_product(RuntimeOrigin::signed(1), id));
+        assert_ok!(Pallet::<Test>::list_all_products(RuntimeOrigin::signed(1)));
+        assert_eq!(Pallet::<Test>::catalog_version(), 2);
+
+        // Uma venda altera o estoque e as vendas
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        assert_eq!(Pallet::<Test>::catalog_version(), 3);
+        assert_eq!(Pallet::<Test>::sales_version(), 1);
+
+        assert_ok!(Pallet::<Test>::get_sale(RuntimeOrigin::signed(1), code));
+        assert_ok!(Pallet::<Test>::update_sale(RuntimeOrigin::signed(1), code, Some(b"Other Seller".to_vec()), None, None));
+        assert_eq!(Pallet::<Test>::sales_version(), 2);
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), code));
+        assert_eq!(Pallet::<Test>::sales_version(), 3);
+
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), id));
+        assert_eq!(Pallet::<Test>::catalog_version(), 4);
+    });
+}
+
+#[test]
+fn it_applies_the_payment_method_surcharge() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 200, 5, Category::Misc);
+
+        assert_noop!(
+            Pallet::<Test>::set_payment_surcharge(RuntimeOrigin::signed(1), PaymentMethod::Credit, Some(250)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(Pallet::<Test>::set_payment_surcharge(RuntimeOrigin::root(), PaymentMethod::Credit, Some(250)));
+
+        // Venda no crédito: 2,5% de 400
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        let sale = Sales::<Test>::get(code).unwrap();
+        assert_eq!(sale.surcharge, 10);
+        assert_eq!(sale.value, 410);
+        System::assert_has_event(Event::SurchargeApplied(PaymentMethod::Credit, 10).into());
+    });
+}
+
+#[test]
+fn it_applies_no_surcharge_without_an_entry() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 200, 5, Category::Misc);
+        assert_ok!(Pallet::<Test>::set_payment_surcharge(RuntimeOrigin::root(), PaymentMethod::Credit, Some(250)));
+
+        assert_ok!(SaleBuilder::new(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], Date::new(3, 2, 2025).unwrap())
+            .payment_method(PaymentMethod::Money)
+            .call());
+        let sale = Sales::<Test>::get(last_registered_sale()).unwrap();
+        assert_eq!(sale.surcharge, 0);
+        assert_eq!(sale.value, 400);
+        assert!(!System::events().iter().any(|record| matches!(record.event, RuntimeEvent::TemplateModule(Event::SurchargeApplied(..)))));
+    });
+}
+
+#[test]
+fn it_prunes_sales_older_than_the_retention_window() {
+    new_test_ext().execute_with(|| {
+        SaleRetentionBlocks::set(10);
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        let old: Vec<u64> = (0..3)
+            .map(|_| register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], date.clone()))
+            .collect();
+        run_to_block(6);
+        let recent = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], date.clone());
+
+        // Bloco 12: as vendas do bloco 1 passaram da retenção, removidas no máximo duas por bloco
+        run_to_block(12);
+        System::assert_has_event(Event::SalesPruned(2).into());
+        assert_eq!(old.iter().filter(|code| Sales::<Test>::contains_key(code)).count(), 1);
+
+        run_to_block(13);
+        System::assert_has_event(Event::SalesPruned(1).into());
+        assert!(old.iter().all(|code| !Sales::<Test>::contains_key(code)));
+        assert!(old.iter().all(|code| ProductSales::<Test>::get(id, code) == 0));
+
+        // A venda recente permanece e a receita agregada é preservada
+        assert!(Sales::<Test>::contains_key(recent));
+        assert_eq!(Pallet::<Test>::daily_revenue(&date), 40);
+    });
+}
+
+#[test]
+fn it_keeps_sales_when_retention_is_zero() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+
+        run_to_block(50);
+        assert!(Sales::<Test>::contains_key(code));
+    });
+}
+
+#[test]
+fn it_prunes_sales_from_the_queue_without_scanning_removed_ones() {
+    new_test_ext().execute_with(|| {
+        SaleRetentionBlocks::set(10);
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        let removed = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], date.clone());
+        let kept = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], date);
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), removed));
+        assert_eq!(SalePruneQueueBounds::<Test>::get(), (0, 2));
+
+        // Antes do fim da retenção, apenas o início da fila é lido
+        run_to_block(11);
+        assert!(Sales::<Test>::contains_key(kept));
+        assert_eq!(SalePruneQueueBounds::<Test>::get(), (0, 2));
+
+        // A posição da venda removida sai da fila sem contar como venda removida
+        run_to_block(12);
+        System::assert_has_event(Event::SalesPruned(1).into());
+        assert!(!Sales::<Test>::contains_key(kept));
+        assert_eq!(SalePruneQueueBounds::<Test>::get(), (2, 2));
+    });
+}
+
+#[test]
+fn it_clones_a_product_as_a_template() {
+    new_test_ext().execute_with(|| {
+        let source = add_test_product(50, 30, 7, Category::Food);
+        assert_ok!(Pallet::<Test>::set_product_tax_category(RuntimeOrigin::signed(1), source, 2));
+        assert_ok!(Pallet::<Test>::set_member_price(RuntimeOrigin::signed(1), source, Some(25)));
+        assert_ok!(Pallet::<Test>::set_reorder_point(RuntimeOrigin::signed(1), source, 3));
+        let promo = (Date::new(1, 2, 2025).unwrap(), Date::new(28, 2, 2025).unwrap(), 10);
+        assert_ok!(Pallet::<Test>::set_promo(RuntimeOrigin::signed(1), source, Some(promo.clone())));
+        assert_ok!(Pallet::<Test>::set_consignor(RuntimeOrigin::signed(1), source, Some(7)));
+
+        assert_ok!(Pallet::<Test>::clone_product(RuntimeOrigin::signed(1), source, b"Clone".to_vec()));
+        let clone = last_added_product();
+        assert_ne!(clone, source);
+        System::assert_last_event(Event::ProductCloned(source, clone).into());
+
+        let original = Products::<Test>::get(source).unwrap();
+        let copy = Products::<Test>::get(clone).unwrap();
+        assert_eq!(copy.name, b"Clone".to_vec());
+        assert_eq!(copy.stock, 0);
+        assert_eq!(copy.price, original.price);
+        assert_eq!(copy.category, original.category);
+        assert_eq!(copy.amount_to_restock, original.amount_to_restock);
+        assert_eq!(copy.restock_date, original.restock_date);
+        assert_eq!(copy.tax_category, 2);
+        assert_eq!(copy.member_price, Some(25));
+        assert_eq!(copy.reorder_point, 3);
+        assert_eq!(copy.promo, Some(promo));
+        assert_eq!(Pallet::<Test>::consignor(clone), Some(7));
+
+        assert_noop!(
+            Pallet::<Test>::clone_product(RuntimeOrigin::signed(1), 999, b"Missing".to_vec()),
+            Error::<Test>::ProductNotFound
+        );
+    });
+}
+
+#[test]
+fn it_signals_lines_meeting_the_bulk_quantity_threshold() {
+    new_test_ext().execute_with(|| {
+        BulkQtyThreshold::set(10);
+        let bulk = add_test_product(100, 10, 5, Category::Misc);
+        let regular = add_test_product(100, 10, 5, Category::Misc);
+
+        register_test_sale(
+            vec![
+                ItemSale { product_id: bulk, amount: 12, discount_percent: None },
+                ItemSale { product_id: regular, amount: 9, discount_percent: None },
+            ],
+            Date::new(3, 2, 2025).unwrap()
+        );
+
+        let signaled: Vec<(u64, u64)> = System::events()
+            .into_iter()
+            .filter_map(|record| match record.event {
+                RuntimeEvent::TemplateModule(Event::BulkQuantitySold(id, amount)) => Some((id, amount)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(signaled, vec![(bulk, 12)]);
+    });
+}
+
+#[test]
+fn it_does_not_signal_bulk_sales_when_disabled() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        register_test_sale(vec![ItemSale { product_id: id, amount: 50, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+
+        assert!(!System::events().iter().any(|record| matches!(record.event, RuntimeEvent::TemplateModule(Event::BulkQuantitySold(..)))));
+    });
+}
+
+#[test]
+fn it_reports_aggregate_store_stats() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Pallet::<Test>::store_stats(), StoreStats::default());
+
+        let shirt = add_test_product(10, 20, 5, Category::Clothing);
+        let food = add_test_product(6, 5, 5, Category::Food);
+        let date = Date::new(3, 2, 2025).unwrap();
+        register_test_sale(vec![ItemSale { product_id: shirt, amount: 7, discount_percent: None }], date.clone());
+        register_test_sale(vec![ItemSale { product_id: food, amount: 1, discount_percent: None }], date);
+
+        assert_eq!(
+            Pallet::<Test>::store_stats(),
+            StoreStats {
+                product_count: 2,
+                sale_count: 2,
+                total_units: 3 + 5,
+                total_inventory_value: 3 * 20 + 5 * 5,
+                // Ambos chegaram ao ponto de reposição
+                restock_needed: 2
+            }
+        );
+
+        // O total de unidades acompanha o estoque mantido por categoria
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), shirt));
+        assert_eq!(Pallet::<Test>::store_stats().total_units, Pallet::<Test>::category_stock_total(Category::Food));
+        assert_eq!(Pallet::<Test>::store_stats().total_units, 5);
+    });
+}
+
+#[test]
+fn it_values_the_inventory_in_whole_units() {
+    new_test_ext().execute_with(|| {
+        // Estoque em milésimos: 2_500 milésimos a 40 valem 100
+        StockUnit::set(1_000);
+        add_test_product(2_500, 40, 1_000, Category::Food);
+        add_test_product(500, 10, 100, Category::Misc);
+
+        assert_eq!(Pallet::<Test>::store_stats().total_inventory_value, 100 + 5);
+    });
+}
+
+#[test]
+fn it_triggers_restock_on_the_reorder_point() {
+    new_test_ext().execute_with(|| {
+        EmitListingPayloads::set(false);
+        let id = add_test_product(8, 10, 5, Category::Misc);
+        assert_eq!(Products::<Test>::get(id).unwrap().reorder_point, 5);
+
+        // Acima do ponto de reposição padrão
+        assert_ok!(Pallet::<Test>::list_products_to_restock(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsToRestockCount(0).into());
+
+        // O ponto de reposição é independente da quantidade reposta e inclui o próprio nível
+        assert_ok!(Pallet::<Test>::set_reorder_point(RuntimeOrigin::signed(1), id, 8));
+        assert_ok!(Pallet::<Test>::list_products_to_restock(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsToRestockCount(1).into());
+        assert_eq!(Products::<Test>::get(id).unwrap().amount_to_restock, 5);
+
+        assert_noop!(
+            Pallet::<Test>::set_reorder_point(RuntimeOrigin::signed(1), 999, 8),
+            Error::<Test>::ProductNotFound
+        );
+    });
+}
+
+#[test]
+fn it_alerts_on_the_same_reorder_point_used_by_listings() {
+    new_test_ext().execute_with(|| {
+        EmitListingPayloads::set(false);
+        let id = add_test_product(8, 10, 5, Category::Misc);
+        assert!(!Pallet::<Test>::alert_state(id));
+
+        // O alerta acompanha o ponto de reposição, e não a quantidade reposta
+        assert_ok!(Pallet::<Test>::set_reorder_point(RuntimeOrigin::signed(1), id, 8));
+        assert!(Pallet::<Test>::alert_state(id));
+        System::assert_has_event(Event::ProductNeedsRestock(id).into());
+        assert_ok!(Pallet::<Test>::list_products_to_restock(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsToRestockCount(1).into());
+        assert_eq!(Pallet::<Test>::store_stats().restock_needed, 1);
+
+        // Sem nível de encerramento, o alerta é encerrado acima do ponto de reposição
+        assert_ok!(ProductUpdate::new(id).stock(9).call());
+        assert!(!Pallet::<Test>::alert_state(id));
+
+        // O nível de encerramento não pode ficar abaixo do ponto de reposição
+        assert_ok!(ProductUpdate::new(id).restock_clear_level(Some(9)).call());
+        assert_noop!(Pallet::<Test>::set_reorder_point(RuntimeOrigin::signed(1), id, 10), Error::<Test>::InvalidClearLevel);
+    });
+}
+
+#[test]
+fn it_restocks_by_amount_to_restock() {
+    new_test_ext().execute_with(|| {
+        EmitListingPayloads::set(false);
+        let id = add_test_product(8, 10, 5, Category::Misc);
+        assert_ok!(Pallet::<Test>::set_reorder_point(RuntimeOrigin::signed(1), id, 10));
+
+        assert_ok!(Pallet::<Test>::restock_product(RuntimeOrigin::signed(1), id));
+        System::assert_last_event(Event::ProductRestocked(id, 13).into());
+        assert_eq!(Products::<Test>::get(id).unwrap().stock, 13);
+
+        assert_ok!(Pallet::<Test>::list_products_to_restock(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsToRestockCount(0).into());
+    });
+}
+
+#[test]
+fn it_lists_the_sales_of_a_product_within_a_date_range() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let other = add_test_product(100, 10, 5, Category::Misc);
+        let sell = |product_id: u64, date: Date| register_test_sale(vec![ItemSale { product_id, amount: 1, discount_percent: None }], date);
+
+        sell(id, Date::new(31, 1, 2025).unwrap());
+        let february = sell(id, Date::new(1, 2, 2025).unwrap());
+        sell(other, Date::new(10, 2, 2025).unwrap());
+        let march = sell(id, Date::new(1, 3, 2025).unwrap());
+        sell(id, Date::new(2, 3, 2025).unwrap());
+
+        assert_ok!(Pallet::<Test>::product_sales_between(
+            RuntimeOrigin::signed(1),
+            id,
+            Date::new(1, 2, 2025).unwrap(),
+            Date::new(1, 3, 2025).unwrap(),
+            10
+        ));
+        System::assert_last_event(Event::ProductSalesBetween(id, vec![february, march]).into());
+
+        assert_noop!(
+            Pallet::<Test>::product_sales_between(RuntimeOrigin::signed(1), 999, Date::new(1, 2, 2025).unwrap(), Date::new(1, 3, 2025).unwrap(), 10),
+            Error::<Test>::ProductNotFound
+        );
+    });
+}
+
+#[test]
+fn it_advances_ids_by_the_configured_step() {
+    new_test_ext().execute_with(|| {
+        IdStep::set(10);
+
+        let ids: Vec<u64> = (0..3).map(|_| add_test_product(100, 10, 5, Category::Misc)).collect();
+        assert_eq!(ids, vec![0, 10, 20]);
+        assert_eq!(Pallet::<Test>::next_product_id(), 30);
+
+        let date = Date::new(3, 2, 2025).unwrap();
+        let codes: Vec<u64> = (0..2)
+            .map(|_| register_test_sale(vec![ItemSale { product_id: 0, amount: 1, discount_percent: None }], date.clone()))
+            .collect();
+        assert_eq!(codes, vec![0, 10]);
+    });
+}
+
+#[test]
+#[should_panic(expected = "IdStep deve ser maior que zero")]
+fn it_refuses_a_zero_id_step() {
+    new_test_ext().execute_with(|| {
+        use frame_support::traits::Hooks;
+
+        IdStep::set(0);
+        <Pallet<Test> as Hooks<u64>>::integrity_test();
+    });
+}
+
+#[test]
+fn it_rejects_a_product_when_the_next_id_would_overflow() {
+    new_test_ext().execute_with(|| {
+        IdStep::set(10);
+        NextProductId::<Test>::put(u64::MAX - 5);
+
+        assert_noop!(
+            ProductBuilder::new(10, 10, 5, Category::Misc).name(b"Overflow".to_vec()).call(),
+            Error::<Test>::Overflow
+        );
+    });
+}
+
+#[test]
+fn it_refunds_weight_for_no_op_updates() {
+    new_test_ext().execute_with(|| {
+        // Pesos de leitura e escrita de um banco de dados real
+        DbWeight::set(RuntimeDbWeight { read: 25_000_000, write: 100_000_000 });
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let product_weight = Call::<Test>::update_product {
+            id,
+            name: None,
+            stock: None,
+            price: None,
+            amount_to_restock: None,
+            restock_date: None,
+            category: None,
+            metadata_hash: None,
+            restock_clear_level: None,
+            attributes: None,
+            unit: None
+        }.get_dispatch_info().weight;
+        let sale_weight = Call::<Test>::update_sale { code: 0, seller: None, date: None, payment_method: None }.get_dispatch_info().weight;
+        // Sem peso real informado, o peso declarado é cobrado integralmente
+        let charged = |info: PostDispatchInfo, declared: Weight| info.actual_weight.map_or(declared, |actual| actual.min(declared));
+
+        // Sem parâmetros ou com os mesmos valores, nada é alterado
+        let noop = ProductUpdate::new(id).call().unwrap();
+        assert!(charged(noop, product_weight).ref_time() < product_weight.ref_time());
+        let noop = ProductUpdate::new(id).stock(100).price(10).call().unwrap();
+        assert!(charged(noop, product_weight).ref_time() < product_weight.ref_time());
+        assert_eq!(Pallet::<Test>::catalog_version(), 1);
+
+        let real = ProductUpdate::new(id).price(20).call().unwrap();
+        assert_eq!(charged(real, product_weight), product_weight);
+
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        let noop = Pallet::<Test>::update_sale(RuntimeOrigin::signed(1), code, None, None, Some(PaymentMethod::Credit)).unwrap();
+        assert!(charged(noop, sale_weight).ref_time() < sale_weight.ref_time());
+        let real = Pallet::<Test>::update_sale(RuntimeOrigin::signed(1), code, None, None, Some(PaymentMethod::Pix)).unwrap();
+        assert_eq!(charged(real, sale_weight), sale_weight);
+    });
+}
+
+#[test]
+fn it_refuses_sales_and_edits_of_a_locked_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let other = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+        let sale = |product_id: u64| SaleBuilder::new(vec![ItemSale { product_id, amount: 1, discount_percent: None }], date.clone()).call();
+
+        assert_noop!(Pallet::<Test>::lock_product(RuntimeOrigin::signed(1), id), sp_runtime::DispatchError::BadOrigin);
+        assert_noop!(Pallet::<Test>::lock_product(RuntimeOrigin::root(), 999), Error::<Test>::ProductNotFound);
+        assert_ok!(Pallet::<Test>::lock_product(RuntimeOrigin::root(), id));
+
+        assert_noop!(sale(id), Error::<Test>::ProductLocked);
+        assert_noop!(
+            ProductUpdate::new(id).price(20).call(),
+            Error::<Test>::ProductLocked
+        );
+        assert_noop!(Pallet::<Test>::transfer_stock(RuntimeOrigin::signed(1), other, id, 5), Error::<Test>::ProductLocked);
+        assert_noop!(Pallet::<Test>::adjust_all_prices(RuntimeOrigin::root(), 5), Error::<Test>::ProductLocked);
+        // Outros produtos seguem disponíveis
+        assert_ok!(sale(other));
+
+        assert_ok!(Pallet::<Test>::unlock_product(RuntimeOrigin::root(), id));
+        assert_ok!(sale(id));
+        assert_eq!(Products::<Test>::get(id).unwrap().stock, 99);
+    });
+}
+
+#[test]
+fn it_computes_change_for_cash_sales() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 25, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+        let items = || vec![ItemSale { product_id: id, amount: 2, discount_percent: None }];
+
+        // Pagamento exato
+        assert_ok!(Pallet::<Test>::register_cash_sale(RuntimeOrigin::signed(1), b"Cashier".to_vec(), items(), Vec::new(), date.clone(), 50));
+        let exact = last_registered_sale();
+        System::assert_last_event(Event::ChangeDue(exact, 0).into());
+        assert_eq!(Sales::<Test>::get(exact).unwrap().payment_method, PaymentMethod::Money);
+
+        // Pagamento com troco
+        assert_ok!(Pallet::<Test>::register_cash_sale(RuntimeOrigin::signed(1), b"Cashier".to_vec(), items(), Vec::new(), date.clone(), 70));
+        System::assert_last_event(Event::ChangeDue(last_registered_sale(), 20).into());
+    });
+}
+
+#[test]
+fn it_rejects_cash_sales_with_insufficient_tender() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 25, 5, Category::Misc);
+
+        assert_noop!(
+            Pallet::<Test>::register_cash_sale(
+                RuntimeOrigin::signed(1),
+                b"Cashier".to_vec(),
+                vec![ItemSale { product_id: id, amount: 2, discount_percent: None }],
+                Vec::new(),
+                Date::new(3, 2, 2025).unwrap(),
+                49
+            ),
+            Error::<Test>::InsufficientTender
+        );
+        assert_eq!(Products::<Test>::get(id).unwrap().stock, 100);
+    });
+}
+
+#[test]
+fn it_enforces_category_stock_caps() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Pallet::<Test>::set_category_stock_cap(RuntimeOrigin::root(), Category::Food, Some(100)));
+
+        let first = add_test_product(60, 10, 5, Category::Food);
+        add_test_product(40, 10, 5, Category::Food);
+        // Outras categorias não são afetadas
+        add_test_product(500, 10, 5, Category::Misc);
+        assert_eq!(Pallet::<Test>::category_stock_total(Category::Food), 100);
+
+        // Categoria cheia: novos produtos e reposições são recusados
+        assert_noop!(Pallet::<Test>::restock_product(RuntimeOrigin::signed(1), first), Error::<Test>::CategoryCapExceeded);
+        assert_noop!(
+            ProductBuilder::new(1, 10, 5, Category::Food).name(b"Extra".to_vec()).call(),
+            Error::<Test>::CategoryCapExceeded
+        );
+        assert_noop!(
+            ProductUpdate::new(first).stock(61).call(),
+            Error::<Test>::CategoryCapExceeded
+        );
+
+        // Vendas liberam espaço na categoria
+        register_test_sale(vec![ItemSale { product_id: first, amount: 10, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        assert_eq!(Pallet::<Test>::category_stock_total(Category::Food), 90);
+        assert_ok!(Pallet::<Test>::restock_product(RuntimeOrigin::signed(1), first));
+        assert_eq!(Pallet::<Test>::category_stock_total(Category::Food), 95);
+    });
+}
+
+#[test]
+fn it_indexes_key_events_by_topic() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let added = System::events().into_iter().find(|record| record.event == Event::ProductAdded(id).into()).unwrap();
+        assert_eq!(added.topics, vec![Pallet::<Test>::product_topic(id)]);
+
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        let registered = System::events().pop().unwrap();
+        assert_eq!(registered.event, Event::SaleRegistered(code).into());
+        assert_eq!(
+            registered.topics,
+            vec![Pallet::<Test>::sale_topic(code), Pallet::<Test>::seller_topic(b"Test Seller"), Pallet::<Test>::product_topic(id)]
+        );
+    });
+}
+
+#[test]
+fn it_charges_the_member_price_to_members() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 50, 5, Category::Misc);
+        let sell = |is_member: bool| {
+            assert_ok!(SaleBuilder::new(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], Date::new(3, 2, 2025).unwrap())
+                .member(is_member)
+                .call());
+            Sales::<Test>::get(last_registered_sale()).unwrap().value
+        };
+
+        // Sem preço para membros, todos pagam o preço padrão
+        assert_eq!(sell(true), 100);
+
+        assert_noop!(
+            Pallet::<Test>::set_member_price(RuntimeOrigin::signed(1), id, Some(51)),
+            Error::<Test>::InvalidMemberPrice
+        );
+        assert_ok!(Pallet::<Test>::set_member_price(RuntimeOrigin::signed(1), id, Some(40)));
+
+        assert_eq!(sell(true), 80);
+        assert_eq!(sell(false), 100);
+
+        // O preço padrão não pode ficar abaixo do preço para membros
+        assert_noop!(
+            ProductUpdate::new(id).price(30).call(),
+            Error::<Test>::InvalidMemberPrice
+        );
+    });
+}
+
+#[test]
+fn it_resets_the_sale_code_above_existing_sales() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+        let codes: Vec<u64> = (0..3)
+            .map(|_| register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], date.clone()))
+            .collect();
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), codes[2]));
+
+        // O contador pode voltar para logo após a maior venda restante
+        assert_ok!(Pallet::<Test>::reset_sale_code(RuntimeOrigin::root(), 2));
+        System::assert_last_event(Event::SaleCodeReset(2).into());
+        assert_eq!(register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], date), 2);
+    });
+}
+
+#[test]
+fn it_rejects_a_sale_code_reset_that_would_collide() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+
+        assert_noop!(Pallet::<Test>::reset_sale_code(RuntimeOrigin::root(), code), Error::<Test>::CodeWouldCollide);
+        assert_noop!(Pallet::<Test>::reset_sale_code(RuntimeOrigin::signed(1), 10), sp_runtime::DispatchError::BadOrigin);
+    });
+}
+
+#[test]
+fn it_applies_promotions_only_within_their_window() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 50, 5, Category::Misc);
+        let promo = (Date::new(1, 3, 2025).unwrap(), Date::new(31, 3, 2025).unwrap(), 20);
+        assert_ok!(Pallet::<Test>::set_promo(RuntimeOrigin::signed(1), id, Some(promo)));
+
+        // A elegibilidade segue a data on-chain, em segundos desde 01/01/1970
+        let sell = |now: u64| {
+            Now::set(now);
+            let code = register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+            Sales::<Test>::get(code).unwrap().value
+        };
+
+        // Dentro da janela, inclusive nas datas limite
+        assert_eq!(sell(1_740_787_200), 80);
+        assert_eq!(sell(1_743_379_200), 80);
+        // Fora da janela
+        assert_eq!(sell(1_740_700_800), 100);
+        assert_eq!(sell(1_743_465_600), 100);
+    });
+}
+
+#[test]
+fn it_ignores_the_sale_date_when_applying_promotions() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 50, 5, Category::Misc);
+        let promo = (Date::new(1, 3, 2025).unwrap(), Date::new(31, 3, 2025).unwrap(), 20);
+        assert_ok!(Pallet::<Test>::set_promo(RuntimeOrigin::signed(1), id, Some(promo)));
+        let items = || vec![ItemSale { product_id: id, amount: 2, discount_percent: None }];
+
+        // Hoje é 01/04/2025: uma venda com data retroativa dentro da promoção não recebe o desconto
+        Now::set(1_743_465_600);
+        let code = register_test_sale(items(), Date::new(15, 3, 2025).unwrap());
+        let sale = Sales::<Test>::get(code).unwrap();
+        assert_eq!(sale.value, 100);
+        assert_eq!(sale.date, Date::new(15, 3, 2025).unwrap());
+
+        // A validação do carrinho chega ao mesmo total
+        assert_ok!(Pallet::<Test>::validate_cart(RuntimeOrigin::signed(2), items()));
+        System::assert_last_event(Event::CartValid(100).into());
+    });
+}
+
+#[test]
+fn it_rejects_invalid_promotions() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 50, 5, Category::Misc);
+
+        assert_noop!(
+            Pallet::<Test>::set_promo(RuntimeOrigin::signed(1), id, Some((Date::new(31, 3, 2025).unwrap(), Date::new(1, 3, 2025).unwrap(), 20))),
+            Error::<Test>::InvalidPromo
+        );
+        assert_noop!(
+            Pallet::<Test>::set_promo(RuntimeOrigin::signed(1), id, Some((Date::new(1, 3, 2025).unwrap(), Date::new(31, 3, 2025).unwrap(), 101))),
+            Error::<Test>::InvalidPromo
+        );
+    });
+}
+
+#[test]
+fn it_undoes_the_last_removal_within_the_window() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(Pallet::<Test>::undo_last_removal(RuntimeOrigin::signed(1)), Error::<Test>::NothingToUndo);
+
+        let id = add_test_product(100, 10, 5, Category::Food);
+        let product = Products::<Test>::get(id).unwrap();
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), id));
+
+        run_to_block(6);
+        // Desfeita por outro funcionário, a remoção devolve o produto ao dono original
+        assert_ok!(Pallet::<Test>::undo_last_removal(RuntimeOrigin::signed(3)));
+        System::assert_last_event(Event::ProductRestored(id).into());
+        assert_eq!(Products::<Test>::get(id), Some(product));
+        assert_eq!(ProductOwners::<Test>::get(id), Some(1));
+        assert!(OwnerProducts::<Test>::contains_key(1, id));
+        assert!(!OwnerProducts::<Test>::contains_key(3, id));
+        assert_eq!(Pallet::<Test>::category_stock_total(Category::Food), 100);
+        assert_eq!(Pallet::<Test>::last_removed_product(), None);
+
+        // A remoção só pode ser desfeita uma vez
+        assert_noop!(Pallet::<Test>::undo_last_removal(RuntimeOrigin::signed(1)), Error::<Test>::NothingToUndo);
+    });
+}
+
+#[test]
+fn it_rejects_undoing_a_removal_after_the_window() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Food);
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), id));
+
+        run_to_block(7);
+        assert_noop!(Pallet::<Test>::undo_last_removal(RuntimeOrigin::signed(1)), Error::<Test>::UndoWindowExpired);
+        assert!(!Products::<Test>::contains_key(id));
+    });
+}
+
+#[test]
+fn it_lists_products_sorted_by_price() {
+    new_test_ext().execute_with(|| {
+        let mid = add_test_product(10, 20, 5, Category::Misc);
+        let cheap = add_test_product(10, 5, 5, Category::Misc);
+        let tied = add_test_product(10, 20, 5, Category::Misc);
+        let pricey = add_test_product(10, 90, 5, Category::Misc);
+
+        assert_ok!(Pallet::<Test>::list_products_by_price(RuntimeOrigin::signed(1), true, 10));
+        System::assert_last_event(Event::ProductsByPrice(vec![(cheap, 5), (mid, 20), (tied, 20), (pricey, 90)]).into());
+
+        // Em ordem decrescente, empates seguem pelo menor ID
+        assert_ok!(Pallet::<Test>::list_products_by_price(RuntimeOrigin::signed(1), false, 3));
+        System::assert_last_event(Event::ProductsByPrice(vec![(pricey, 90), (mid, 20), (tied, 20)]).into());
+    });
+}
+
+#[test]
+fn it_hides_and_refuses_products_below_the_listable_stock() {
+    new_test_ext().execute_with(|| {
+        MinListableStock::set(10);
+        EmitListingPayloads::set(false);
+        let low = add_test_product(8, 10, 2, Category::Misc);
+        add_test_product(50, 10, 2, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+
+        assert_ok!(Pallet::<Test>::list_all_products(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsListedCount(1).into());
+        assert!(!Pallet::<Test>::is_sellable(low, 1));
+        assert_eq!(Pallet::<Test>::cheapest_in_category(Category::Misc, false).map(|(id, _)| id), Some(1));
+        assert_noop!(
+            SaleBuilder::new(vec![ItemSale { product_id: low, amount: 1, discount_percent: None }], date.clone()).call(),
+            Error::<Test>::ProductNotListable
+        );
+
+        // Após a reposição, o produto volta a ser listado e vendido
+        assert_ok!(Pallet::<Test>::restock_product(RuntimeOrigin::signed(1), low));
+        assert_ok!(Pallet::<Test>::list_all_products(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::ProductsListedCount(2).into());
+        assert!(Pallet::<Test>::is_sellable(low, 1));
+        register_test_sale(vec![ItemSale { product_id: low, amount: 1, discount_percent: None }], date);
+    });
+}
+
+#[test]
+fn it_tracks_the_average_sale_value() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Pallet::<Test>::average_sale_value(), 0);
+
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+        let sell = |amount: u64| register_test_sale(vec![ItemSale { product_id: id, amount, discount_percent: None }], date.clone());
+
+        sell(1);
+        sell(2);
+        let largest = sell(9);
+        // (10 + 20 + 90) / 3
+        assert_eq!(Pallet::<Test>::average_sale_value(), 40);
+
+        // O estorno retira a venda da média
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), largest));
+        assert_eq!(Pallet::<Test>::sale_count(), 2);
+        assert_eq!(Pallet::<Test>::average_sale_value(), 15);
+    });
+}
+
+#[test]
+fn it_stores_product_attributes() {
+    new_test_ext().execute_with(|| {
+        let attributes = vec![(b"size".to_vec(), b"M".to_vec()), (b"color".to_vec(), b"blue".to_vec())];
+        let id = ProductBuilder::new(10, 50, 5, Category::Clothing).name(b"Shirt".to_vec()).attributes(attributes.clone()).add();
+
+        let stored = |id: u64| -> Vec<(Vec<u8>, Vec<u8>)> {
+            Products::<Test>::get(id).unwrap().attributes.into_iter().map(|(key, value)| (key.into_inner(), value.into_inner())).collect()
+        };
+        assert_eq!(stored(id), attributes);
+
+        let updated = vec![(b"voltage".to_vec(), b"220V".to_vec())];
+        assert_ok!(ProductUpdate::new(id).attributes(updated.clone()).call());
+        assert_eq!(stored(id), updated);
+    });
+}
+
+#[test]
+fn it_rejects_attributes_over_the_limits() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(10, 50, 5, Category::Clothing);
+        let update = |attributes: Vec<(Vec<u8>, Vec<u8>)>| {
+            ProductUpdate::new(id).attributes(attributes).call()
+        };
+
+        // Mais atributos que `MaxAttributes`
+        assert_noop!(
+            update(vec![(b"a".to_vec(), b"1".to_vec()), (b"b".to_vec(), b"2".to_vec()), (b"c".to_vec(), b"3".to_vec())]),
+            Error::<Test>::AttributeLimitExceeded
+        );
+        // Chave e valor longos demais
+        assert_noop!(update(vec![(vec![b'k'; 33], b"1".to_vec())]), Error::<Test>::AttributeLimitExceeded);
+        assert_noop!(update(vec![(b"k".to_vec(), vec![b'v'; 65])]), Error::<Test>::AttributeLimitExceeded);
+    });
+}
+
+#[test]
+fn it_registers_a_sale_split_across_payment_methods() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 25, 5, Category::Misc);
+        let payments = vec![(PaymentMethod::Money, 30), (PaymentMethod::Credit, 70)];
+
+        assert_ok!(SaleBuilder::new(vec![ItemSale { product_id: id, amount: 4, discount_percent: None }], Date::new(3, 2, 2025).unwrap())
+            .split(payments.clone())
+            .call());
+        let sale = Sales::<Test>::get(last_registered_sale()).unwrap();
+        assert_eq!(sale.payments, payments);
+        assert_eq!(sale.payment_method, PaymentMethod::Money);
+
+        // Vendas com um único método registram o total nesse método
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        assert_eq!(Sales::<Test>::get(code).unwrap().payments, vec![(PaymentMethod::Credit, 25)]);
+    });
+}
+
+#[test]
+fn it_rejects_split_payments_not_matching_the_total() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 25, 5, Category::Misc);
+        let split = |payments: Vec<(PaymentMethod, u64)>| {
+            SaleBuilder::new(vec![ItemSale { product_id: id, amount: 4, discount_percent: None }], Date::new(3, 2, 2025).unwrap()).split(payments).call()
+        };
+
+        assert_noop!(split(vec![(PaymentMethod::Money, 30), (PaymentMethod::Pix, 60)]), Error::<Test>::PaymentMismatch);
+        assert_noop!(split(Vec::new()), Error::<Test>::PaymentMismatch);
+    });
+}
+
+#[test]
+fn it_splits_the_part_of_the_total_not_covered_by_a_gift_card() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 25, 5, Category::Misc);
+        let card = [1u8; 16];
+        assert_ok!(Pallet::<Test>::issue_gift_card(RuntimeOrigin::root(), card, 40));
+        let split = |payments: Vec<(PaymentMethod, u64)>| {
+            SaleBuilder::new(vec![ItemSale { product_id: id, amount: 4, discount_percent: None }], Date::new(3, 2, 2025).unwrap())
+                .split(payments)
+                .gift_card(card)
+                .call()
+        };
+
+        // As parcelas cobrem o total de 100 menos os 40 do cartão
+        assert_noop!(split(vec![(PaymentMethod::Money, 30), (PaymentMethod::Pix, 70)]), Error::<Test>::PaymentMismatch);
+        assert_ok!(split(vec![(PaymentMethod::Money, 20), (PaymentMethod::Pix, 40)]));
+
+        let sale = Sales::<Test>::get(last_registered_sale()).unwrap();
+        assert_eq!(sale.value, 100);
+        assert_eq!(sale.gift_card, Some((card, 40)));
+        assert_eq!(sale.payments, vec![(PaymentMethod::Money, 20), (PaymentMethod::Pix, 40)]);
+        assert_eq!(Pallet::<Test>::gift_cards(card), Some(0));
+    });
+}
+
+#[test]
+fn it_backs_out_tax_from_tax_inclusive_prices() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 110, 5, Category::Misc);
+        let item = vec![ItemSale { product_id: id, amount: 1, discount_percent: None }];
+
+        // Preço sem imposto: 10% calculado sobre os 110 exibidos
+        let exclusive = Sales::<Test>::get(register_test_sale(item.clone(), Date::new(3, 2, 2025).unwrap())).unwrap();
+        assert_eq!((exclusive.value, exclusive.net, exclusive.tax), (110, 110, 11));
+
+        // Preço com imposto: os 110 exibidos já contêm os 10%
+        assert_ok!(Pallet::<Test>::set_prices_include_tax(RuntimeOrigin::root(), true));
+        let inclusive = Sales::<Test>::get(register_test_sale(item, Date::new(4, 2, 2025).unwrap())).unwrap();
+        assert_eq!((inclusive.value, inclusive.net, inclusive.tax), (110, 100, 10));
+    });
+}
+
+#[test]
+fn it_rounds_tax_backed_out_of_inclusive_prices() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 100, 5, Category::Misc);
+        assert_ok!(Pallet::<Test>::set_prices_include_tax(RuntimeOrigin::root(), true));
+
+        // 100 / 1,1 = 90,9..., arredondado para 91 no modo mais próximo
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        let sale = Sales::<Test>::get(code).unwrap();
+        assert_eq!((sale.net, sale.tax), (91, 9));
+        assert_eq!(sale.net + sale.tax, sale.value);
+    });
+}
+
+#[test]
+fn it_lists_only_categories_with_products() {
+    new_test_ext().execute_with(|| {
+        assert!(Pallet::<Test>::used_categories().is_empty());
+
+        let food = add_test_product(100, 10, 5, Category::Food);
+        let first_misc = add_test_product(100, 10, 5, Category::Misc);
+        let second_misc = add_test_product(100, 10, 5, Category::Misc);
+        assert_eq!(Pallet::<Test>::used_categories(), vec![Category::Food, Category::Misc]);
+        assert_eq!(Pallet::<Test>::category_counts(Category::Misc), 2);
+
+        // A categoria continua em uso enquanto tiver algum produto
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), first_misc));
+        assert_eq!(Pallet::<Test>::used_categories(), vec![Category::Food, Category::Misc]);
+
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), second_misc));
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), food));
+        assert!(Pallet::<Test>::used_categories().is_empty());
+        assert!(!CategoryCounts::<Test>::contains_key(Category::Misc));
+    });
+}
+
+#[test]
+fn it_amends_sale_items_reconciling_stock_and_value() {
+    new_test_ext().execute_with(|| {
+        let first = add_test_product(100, 10, 5, Category::Misc);
+        let second = add_test_product(100, 20, 5, Category::Food);
+        let code = register_test_sale(
+            vec![
+                ItemSale { product_id: first, amount: 3, discount_percent: None },
+                ItemSale { product_id: second, amount: 2, discount_percent: None },
+            ],
+            Date::new(3, 2, 2025).unwrap()
+        );
+        assert_eq!(Sales::<Test>::get(code).unwrap().value, 70);
+
+        // Aumento de um item e redução do outro
+        assert_ok!(Pallet::<Test>::amend_sale_items(
+            RuntimeOrigin::signed(1),
+            code,
+            vec![
+                ItemSale { product_id: first, amount: 5, discount_percent: None },
+                ItemSale { product_id: second, amount: 1, discount_percent: None },
+            ]
+        ));
+        System::assert_last_event(Event::SaleAmended(code).into());
+
+        assert_eq!(Products::<Test>::get(first).unwrap().stock, 95);
+        assert_eq!(Products::<Test>::get(second).unwrap().stock, 99);
+        let sale = Sales::<Test>::get(code).unwrap();
+        assert_eq!(sale.value, 70);
+        assert_eq!(sale.lines.iter().map(|line| (line.product_id, line.amount, line.value)).collect::<Vec<_>>(), vec![(first, 5, 50), (second, 1, 20)]);
+        assert_eq!(Pallet::<Test>::daily_revenue(Date::new(3, 2, 2025).unwrap()), 70);
+
+        // Redução que altera o valor total
+        assert_ok!(Pallet::<Test>::amend_sale_items(
+            RuntimeOrigin::signed(1),
+            code,
+            vec![ItemSale { product_id: first, amount: 1, discount_percent: None }]
+        ));
+        assert_eq!(Products::<Test>::get(first).unwrap().stock, 99);
+        assert_eq!(Products::<Test>::get(second).unwrap().stock, 100);
+        let sale = Sales::<Test>::get(code).unwrap();
+        assert_eq!(sale.value, 10);
+        assert_eq!(sale.products, vec![first]);
+        assert_eq!(Pallet::<Test>::daily_revenue(Date::new(3, 2, 2025).unwrap()), 10);
+    });
+}
+
+#[test]
+fn it_amends_sale_items_at_the_prices_recorded_on_the_sale() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 100, 5, Category::Misc);
+        let card = [2u8; 16];
+        assert_ok!(Pallet::<Test>::issue_gift_card(RuntimeOrigin::root(), card, 50));
+        assert_ok!(SaleBuilder::new(vec![ItemSale { product_id: id, amount: 2, discount_percent: Some(10) }], Date::new(3, 2, 2025).unwrap())
+            .gift_card(card)
+            .call());
+        let code = last_registered_sale();
+
+        // Mudanças de preço após a venda não afetam a correção
+        Products::<Test>::mutate(id, |product| {
+            let product = product.as_mut().unwrap();
+            product.price = 500;
+            product.price_floor = Some(400);
+        });
+        assert_ok!(Pallet::<Test>::amend_sale_items(
+            RuntimeOrigin::signed(1),
+            code,
+            vec![ItemSale { product_id: id, amount: 3, discount_percent: None }]
+        ));
+
+        let sale = Sales::<Test>::get(code).unwrap();
+        assert_eq!(sale.value, 270);
+        assert_eq!((sale.lines[0].unit_price, sale.lines[0].discount_percent), (100, Some(10)));
+        // Os 50 do cartão-presente continuam fora do pagamento
+        assert_eq!(sale.payments, vec![(PaymentMethod::Credit, 220)]);
+
+        // O novo total não pode ficar abaixo do que já foi debitado do cartão
+        assert_noop!(
+            Pallet::<Test>::amend_sale_items(RuntimeOrigin::signed(1), code, vec![ItemSale { product_id: id, amount: 1, discount_percent: Some(60) }]),
+            Error::<Test>::PaymentMismatch
+        );
+    });
+}
+
+#[test]
+fn it_keeps_category_stock_caps_when_amendments_return_stock() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(10, 10, 5, Category::Misc);
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 4, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        assert_ok!(Pallet::<Test>::set_category_stock_cap(RuntimeOrigin::root(), Category::Misc, Some(7)));
+
+        assert_noop!(
+            Pallet::<Test>::amend_sale_items(RuntimeOrigin::signed(1), code, vec![ItemSale { product_id: id, amount: 2, discount_percent: None }]),
+            Error::<Test>::CategoryCapExceeded
+        );
+        assert_ok!(Pallet::<Test>::amend_sale_items(RuntimeOrigin::signed(1), code, vec![ItemSale { product_id: id, amount: 3, discount_percent: None }]));
+        assert_eq!(Pallet::<Test>::category_stock_total(Category::Misc), 7);
+    });
+}
+
+#[test]
+fn it_rejects_invalid_sale_amendments() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let id = add_test_product(5, 10, 1, Category::Misc);
+        let other = add_test_product(100, 10, 5, Category::Misc);
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+
+        // Estoque insuficiente para o aumento
+        assert_noop!(
+            Pallet::<Test>::amend_sale_items(RuntimeOrigin::signed(1), code, vec![ItemSale { product_id: id, amount: 6, discount_percent: None }]),
+            Error::<Test>::InsufficientStock
+        );
+        // Produto fora da venda
+        assert_noop!(
+            Pallet::<Test>::amend_sale_items(RuntimeOrigin::signed(1), code, vec![ItemSale { product_id: other, amount: 1, discount_percent: None }]),
+            Error::<Test>::ProductNotInSale
+        );
+
+        // Prazo de correção encerrado
+        System::set_block_number(12);
+        assert_noop!(
+            Pallet::<Test>::amend_sale_items(RuntimeOrigin::signed(1), code, vec![ItemSale { product_id: id, amount: 1, discount_percent: None }]),
+            Error::<Test>::EditWindowExpired
+        );
+    });
+}
+
+#[test]
+fn it_rejects_empty_updates_in_strict_mode() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+
+        // Sem o modo estrito, a atualização vazia é aceita sem alterar nada
+        assert_ok!(ProductUpdate::new(id).call());
+        assert_ok!(Pallet::<Test>::update_sale(RuntimeOrigin::signed(1), code, None, None, None));
+
+        RejectEmptyUpdates::set(true);
+        assert_noop!(
+            ProductUpdate::new(id).call(),
+            Error::<Test>::NothingToUpdate
+        );
+        assert_noop!(Pallet::<Test>::update_sale(RuntimeOrigin::signed(1), code, None, None, None), Error::<Test>::NothingToUpdate);
+
+        // Atualizações com algum campo continuam aceitas
+        assert_ok!(ProductUpdate::new(id).price(20).call());
+        assert_ok!(Pallet::<Test>::update_sale(RuntimeOrigin::signed(1), code, None, None, Some(PaymentMethod::Pix)));
+    });
+}
+
+#[test]
+fn it_checks_whether_products_and_sales_exist() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+
+        assert!(Pallet::<Test>::product_exists(id));
+        assert!(!Pallet::<Test>::product_exists(999));
+        assert!(Pallet::<Test>::sale_exists(code));
+        assert!(!Pallet::<Test>::sale_exists(999));
+    });
+}
+
+#[test]
+fn it_sells_baskets_whose_intermediate_values_exceed_u64() {
+    new_test_ext().execute_with(|| {
+        // Estoque em milésimos: preço * quantidade estoura u64, mas o valor de 2 unidades cabe
+        StockUnit::set(1_000);
+        let id = add_test_product(10_000, u64::MAX / 4, 1_000, Category::Misc);
+
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 2_000, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        assert_eq!(Sales::<Test>::get(code).unwrap().value, u64::MAX / 4 * 2);
+    });
+}
+
+#[test]
+fn it_rejects_baskets_whose_total_exceeds_u64() {
+    new_test_ext().execute_with(|| {
+        StockUnit::set(1_000);
+        let id = add_test_product(10_000, u64::MAX / 4, 1_000, Category::Misc);
+
+        assert_err!(
+            SaleBuilder::new(vec![ItemSale { product_id: id, amount: 5_000, discount_percent: None }], Date::new(3, 2, 2025).unwrap()).call(),
+            Error::<Test>::Overflow
+        );
+        assert_eq!(Products::<Test>::get(id).unwrap().stock, 10_000);
+    });
+}
+
+#[test]
+fn it_emits_the_sale_category_breakdown() {
+    new_test_ext().execute_with(|| {
+        let food = add_test_product(100, 10, 5, Category::Food);
+        let snack = add_test_product(100, 4, 5, Category::Food);
+        let gadget = add_test_product(100, 50, 5, Category::Electronic);
+
+        let code = register_test_sale(
+            vec![
+                ItemSale { product_id: food, amount: 2, discount_percent: None },
+                ItemSale { product_id: gadget, amount: 1, discount_percent: None },
+                ItemSale { product_id: snack, amount: 5, discount_percent: None },
+            ],
+            Date::new(3, 2, 2025).unwrap()
+        );
+
+        System::assert_has_event(Event::SaleCategoryBreakdown(code, vec![(Category::Electronic, 50), (Category::Food, 40)]).into());
+    });
+}
+
+#[test]
+fn it_accumulates_sales_aggregates_beyond_u64() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Food);
+        let date = Date::new(3, 2, 2025).unwrap();
+        TotalSalesValue::<Test>::put(u64::MAX as u128 - 5);
+        DailyRevenue::<Test>::insert(&date, u64::MAX as u128 - 5);
+        CategoryRevenue::<Test>::insert(Category::Food, u64::MAX as u128);
+
+        register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], date.clone());
+
+        assert_eq!(Pallet::<Test>::total_sales_value(), u64::MAX as u128 + 15);
+        assert_eq!(Pallet::<Test>::daily_revenue(&date), u64::MAX as u128 + 15);
+        assert_eq!(Pallet::<Test>::category_revenue(Category::Food), u64::MAX as u128 + 20);
+
+        // O total nunca dá a volta: no limite de u128, a venda é rejeitada
+        TotalSalesValue::<Test>::put(u128::MAX);
+        assert_noop!(
+            SaleBuilder::new(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], date.clone()).call(),
+            Error::<Test>::Overflow
+        );
+    });
+}
+
+#[test]
+fn it_lists_the_most_recent_sales_newest_first() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+        let codes: Vec<u64> = (1..=5)
+            .map(|amount| register_test_sale(vec![ItemSale { product_id: id, amount, discount_percent: None }], date.clone()))
+            .collect();
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), codes[3]));
+
+        assert_ok!(Pallet::<Test>::recent_sales(RuntimeOrigin::signed(1), 3));
+        System::assert_last_event(Event::RecentSales(vec![(codes[4], 50), (codes[2], 30), (codes[1], 20)]).into());
+
+        // Acima de `MaxPageSize`, o limite é reduzido e todas as vendas cabem
+        assert_ok!(Pallet::<Test>::recent_sales(RuntimeOrigin::signed(1), 100));
+        System::assert_last_event(Event::RecentSales(vec![(codes[4], 50), (codes[2], 30), (codes[1], 20), (codes[0], 10)]).into());
+    });
+}
+
+#[test]
+fn it_lists_recent_sales_past_removed_ones_up_to_the_scan_limit() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+        let codes: Vec<u64> = (1..=10)
+            .map(|amount| register_test_sale(vec![ItemSale { product_id: id, amount: amount % 3 + 1, discount_percent: None }], date.clone()))
+            .collect();
+        for code in &codes[3..9] {
+            assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), *code));
+        }
+
+        // Seis vendas removidas entre as recentes não reduzem o resultado
+        let info = Pallet::<Test>::recent_sales(RuntimeOrigin::signed(1), 3).unwrap();
+        System::assert_last_event(Event::RecentSales(vec![(codes[9], 20), (codes[2], 10), (codes[1], 30)]).into());
+        assert_eq!(info.actual_weight, Some(DbWeight::get().reads(10).saturating_add(Weight::from_parts(10_000, 0))));
+
+        // A busca para em `MaxRecentSalesScan` códigos
+        assert_ok!(Pallet::<Test>::recent_sales(RuntimeOrigin::signed(1), 5));
+        System::assert_last_event(Event::RecentSales(vec![(codes[9], 20), (codes[2], 10)]).into());
+    });
+}
+
+#[test]
+fn it_enforces_the_minimum_margin() {
+    new_test_ext().execute_with(|| {
+        // Margem mínima de 20%
+        MinMarginBasisPoints::set(2_000);
+        let id = add_test_product(100, 100, 5, Category::Misc);
+
+        // Custo de 80 sobre preço de 100: margem de exatamente 20%
+        assert_ok!(Pallet::<Test>::set_cost_price(RuntimeOrigin::signed(1), id, Some(80)));
+        assert_noop!(Pallet::<Test>::set_cost_price(RuntimeOrigin::signed(1), id, Some(81)), Error::<Test>::MarginTooLow);
+
+        // Preço reduzido abaixo da margem mínima
+        assert_noop!(
+            ProductUpdate::new(id).price(99).call(),
+            Error::<Test>::MarginTooLow
+        );
+        assert_ok!(ProductUpdate::new(id).price(120).call());
+
+        // Sem custo informado, qualquer preço é aceito
+        assert_ok!(Pallet::<Test>::set_cost_price(RuntimeOrigin::signed(1), id, None));
+        assert_ok!(ProductUpdate::new(id).price(1).call());
+    });
+}
+
+#[test]
+fn it_sets_the_restock_date_of_a_whole_category() {
+    new_test_ext().execute_with(|| {
+        let bread = add_test_product(100, 10, 5, Category::Food);
+        let milk = add_test_product(100, 10, 5, Category::Food);
+        let shirt = add_test_product(100, 10, 5, Category::Clothing);
+        let original = Products::<Test>::get(shirt).unwrap().restock_date;
+        let date = Date::new(1, 12, 2025).unwrap();
+
+        assert_ok!(Pallet::<Test>::set_category_restock_date(RuntimeOrigin::signed(1), Category::Food, date.clone()));
+        System::assert_last_event(Event::CategoryRestockDateSet(Category::Food, 2).into());
+
+        assert_eq!(Products::<Test>::get(bread).unwrap().restock_date, date);
+        assert_eq!(Products::<Test>::get(milk).unwrap().restock_date, date);
+        assert_eq!(Products::<Test>::get(shirt).unwrap().restock_date, original);
+
+        assert_noop!(
+            Pallet::<Test>::set_category_restock_date(RuntimeOrigin::signed(1), Category::Food, Date { day: 31, month: 2, year: 2025 }),
+            Error::<Test>::InvalidDate
+        );
+    });
+}
+
+#[test]
+fn it_tracks_sales_velocity_within_the_window() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+        let sell = |amount: u64| register_test_sale(vec![ItemSale { product_id: id, amount, discount_percent: None }], date.clone());
+
+        sell(2);
+        sell(1);
+        System::set_block_number(5);
+        sell(3);
+        assert_eq!(Pallet::<Test>::sales_velocity(id), 6);
+        // Vendas do mesmo bloco ficam na mesma amostra
+        assert_eq!(RecentSales::<Test>::get(id).into_inner(), vec![(1, 3), (5, 3)]);
+
+        // No bloco 12, as vendas do bloco 1 saem da janela de 10 blocos
+        System::set_block_number(12);
+        assert_eq!(Pallet::<Test>::sales_velocity(id), 3);
+        sell(4);
+        assert_eq!(RecentSales::<Test>::get(id).into_inner(), vec![(5, 3), (12, 4)]);
+        assert_eq!(Pallet::<Test>::sales_velocity(id), 7);
+
+        System::set_block_number(30);
+        assert_eq!(Pallet::<Test>::sales_velocity(id), 0);
+    });
+}
+
+#[test]
+fn it_clears_sales_velocity_when_a_product_leaves_the_catalog() {
+    new_test_ext().execute_with(|| {
+        let removed = add_test_product(100, 10, 5, Category::Misc);
+        let archived = add_test_product(3, 10, 5, Category::Misc);
+        register_test_sale(vec![
+            ItemSale { product_id: removed, amount: 2, discount_percent: None },
+            ItemSale { product_id: archived, amount: 1, discount_percent: None },
+        ], Date::new(3, 2, 2025).unwrap());
+
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), removed));
+        assert_ok!(Pallet::<Test>::archive_below_stock(RuntimeOrigin::root(), 2));
+
+        // Um ID reaproveitado não herda as amostras do produto anterior
+        assert!(RecentSales::<Test>::get(removed).is_empty());
+        assert!(RecentSales::<Test>::get(archived).is_empty());
+        assert_eq!(Pallet::<Test>::sales_velocity(removed), 0);
+    });
+}
+
+#[test]
+fn it_only_refunds_sales_within_the_refund_window() {
+    new_test_ext().execute_with(|| {
+        RefundWindowBlocks::set(10);
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+        let recent = register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], date.clone());
+        let old = register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], date.clone());
+
+        System::set_block_number(11);
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), recent));
+
+        System::set_block_number(12);
+        assert_noop!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), old), Error::<Test>::RefundWindowExpired);
+
+        // Com prazo zero, a venda pode ser estornada a qualquer momento
+        RefundWindowBlocks::set(0);
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), old));
+    });
+}
+
+#[test]
+fn it_reports_the_encoded_size_of_a_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let product = Products::<Test>::get(id).unwrap();
+
+        assert_eq!(Pallet::<Test>::product_encoded_size(id), Some(product.encode().len() as u32));
+        assert_eq!(Pallet::<Test>::product_encoded_size(999), None);
+    });
+}
+
+#[test]
+fn it_signals_only_the_first_sale_of_a_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 10, 5, Category::Misc);
+        let date = Date::new(3, 2, 2025).unwrap();
+        let first_sold = || System::events()
+            .into_iter()
+            .filter(|record| matches!(record.event, RuntimeEvent::TemplateModule(Event::ProductFirstSold(..))))
+            .count();
+
+        register_test_sale(vec![ItemSale { product_id: id, amount: 1, discount_percent: None }], date.clone());
+        System::assert_has_event(Event::ProductFirstSold(id, 1).into());
+
+        System::set_block_number(2);
+        register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], date);
+        assert_eq!(first_sold(), 1);
+    });
+}
+
+#[test]
+fn it_removes_low_value_products_immediately() {
+    new_test_ext().execute_with(|| {
+        DeletionApprovalThreshold::set(1_000);
+        // 100 unidades a 10: exatamente o limite
+        let id = add_test_product(100, 10, 5, Category::Misc);
+
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), id));
+        System::assert_last_event(Event::ProductRemoved(id).into());
+        assert!(!Products::<Test>::contains_key(id));
+    });
+}
+
+#[test]
+fn it_scales_the_removal_threshold_by_the_stock_unit() {
+    new_test_ext().execute_with(|| {
+        DeletionApprovalThreshold::set(1_000);
+        // Estoque em milésimos: 50_000 milésimos a 20 valem 1_000, exatamente o limite
+        StockUnit::set(1_000);
+        let cheap = add_test_product(50_000, 20, 5_000, Category::Misc);
+        let expensive = add_test_product(50_000, 21, 5_000, Category::Misc);
+
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), cheap));
+        System::assert_last_event(Event::ProductRemoved(cheap).into());
+
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), expensive));
+        System::assert_last_event(Event::DeletionPending(expensive).into());
+    });
+}
+
+#[test]
+fn it_requires_approval_to_remove_high_value_products() {
+    new_test_ext().execute_with(|| {
+        DeletionApprovalThreshold::set(1_000);
+        let id = add_test_product(100, 11, 5, Category::Misc);
+
+        assert_noop!(Pallet::<Test>::approve_deletion(RuntimeOrigin::root(), id), Error::<Test>::NoPendingDeletion);
+
+        assert_ok!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), id));
+        System::assert_last_event(Event::DeletionPending(id).into());
+        assert!(Products::<Test>::contains_key(id));
+        assert_eq!(Pallet::<Test>::pending_deletions(id), Some(1));
+        assert_noop!(Pallet::<Test>::remove_product(RuntimeOrigin::signed(1), id), Error::<Test>::DeletionAlreadyPending);
+
+        // Apenas administradores aprovam a remoção
+        assert_noop!(Pallet::<Test>::approve_deletion(RuntimeOrigin::signed(1), id), sp_runtime::DispatchError::BadOrigin);
+        assert_ok!(Pallet::<Test>::approve_deletion(RuntimeOrigin::root(), id));
+        System::assert_last_event(Event::ProductRemoved(id).into());
+        assert!(!Products::<Test>::contains_key(id));
+        assert!(!PendingDeletions::<Test>::contains_key(id));
+    });
+}
+
+#[test]
+fn it_builds_an_itemized_receipt() {
+    new_test_ext().execute_with(|| {
+        let pen = add_test_product(100, 10, 5, Category::Misc);
+        let book = add_test_product(100, 25, 5, Category::Misc);
+        let code = register_test_sale(
+            vec![
+                ItemSale { product_id: pen, amount: 3, discount_percent: Some(10) },
+                ItemSale { product_id: book, amount: 2, discount_percent: None },
+            ],
+            Date::new(3, 2, 2025).unwrap()
+        );
+
+        let receipt = Pallet::<Test>::receipt(code).unwrap();
+        assert_eq!(receipt.lines, vec![
+            ReceiptLine { product_id: pen, name: b"Test Product".to_vec(), unit_price: 10, amount: 3, discount: 3, line_total: 27, tax: 3 },
+            ReceiptLine { product_id: book, name: b"Test Product".to_vec(), unit_price: 25, amount: 2, discount: 0, line_total: 50, tax: 5 },
+        ]);
+        assert_eq!(receipt.subtotal, 77);
+        // 10% de imposto por item: 2,7 arredondado para 3, mais 5
+        assert_eq!(receipt.tax, 8);
+        assert_eq!(receipt.total, 77);
+
+        // Mudanças de preço após a venda não alteram o recibo
+        assert_ok!(ProductUpdate::new(book).price(40).call());
+        assert_eq!(Pallet::<Test>::receipt(code).unwrap(), receipt);
+
+        assert_eq!(Pallet::<Test>::receipt(999), None);
+    });
+}
+
+#[test]
+fn it_bumps_the_catalog_version_when_the_consignor_changes() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 100, 5, Category::Misc);
+        let version = Pallet::<Test>::catalog_version();
+
+        assert_ok!(Pallet::<Test>::set_consignor(RuntimeOrigin::signed(1), id, Some(7)));
+        assert_eq!(Pallet::<Test>::catalog_version(), version + 1);
+
+        assert_ok!(Pallet::<Test>::set_consignor(RuntimeOrigin::signed(1), id, None));
+        assert_eq!(Pallet::<Test>::catalog_version(), version + 2);
+    });
+}
+
+#[test]
+fn it_pays_the_consignor_the_cost_of_consigned_items() {
+    new_test_ext().execute_with(|| {
+        use frame_support::traits::fungible::{Inspect, Mutate};
+
+        let consigned = add_test_product(100, 100, 5, Category::Misc);
+        let own = add_test_product(100, 50, 5, Category::Misc);
+        assert_ok!(Pallet::<Test>::set_cost_price(RuntimeOrigin::signed(1), consigned, Some(60)));
+        assert_ok!(Pallet::<Test>::set_cost_price(RuntimeOrigin::signed(1), own, Some(30)));
+        assert_ok!(Pallet::<Test>::set_consignor(RuntimeOrigin::signed(1), consigned, Some(7)));
+        assert_ok!(<Balances as Mutate<u64>>::mint_into(&2, 500));
+
+        let items = vec![
+            ItemSale { product_id: consigned, amount: 2, discount_percent: None },
+            ItemSale { product_id: own, amount: 1, discount_percent: None },
+        ];
+        assert_ok!(SaleBuilder::new(items, Date::new(3, 2, 2025).unwrap()).origin(RuntimeOrigin::signed(2)).call());
+
+        // Apenas o custo dos itens em consignação vai para o consignante, e a loja recebe o restante
+        System::assert_has_event(Event::ConsignmentSettled(7, 120).into());
+        assert_eq!(Balances::balance(&7), 120);
+        assert_eq!(Balances::balance(&2), 380);
+        let sale = Sales::<Test>::get(last_registered_sale()).unwrap();
+        assert_eq!(sale.value, 250);
+        assert_eq!(sale.payments, vec![(PaymentMethod::Credit, 130)]);
+
+        // A parcela do consignante fica fora da receita da loja
+        assert_eq!(Pallet::<Test>::daily_revenue(&Date::new(3, 2, 2025).unwrap()), 130);
+        assert_eq!(Pallet::<Test>::category_revenue(&Category::Misc), 130);
+        assert_eq!(Pallet::<Test>::total_sales_value(), 130);
+
+        // O estorno retira da receita apenas a parcela da loja e devolve o repasse ao comprador
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), last_registered_sale()));
+        System::assert_has_event(Event::ConsignmentRefunded(7, 120).into());
+        assert_eq!(Balances::balance(&7), 0);
+        assert_eq!(Balances::balance(&2), 500);
+        assert_eq!(Pallet::<Test>::daily_revenue(&Date::new(3, 2, 2025).unwrap()), 0);
+        assert_eq!(Pallet::<Test>::category_revenue(&Category::Misc), 0);
+        assert_eq!(Pallet::<Test>::total_sales_value(), 0);
+    });
+}
+
+#[test]
+fn it_charges_the_buyer_only_the_store_share_of_consigned_sales() {
+    new_test_ext().execute_with(|| {
+        use frame_support::traits::fungible::{Inspect, Mutate};
+
+        let consigned = add_test_product(100, 100, 5, Category::Misc);
+        assert_ok!(Pallet::<Test>::set_cost_price(RuntimeOrigin::signed(1), consigned, Some(60)));
+        assert_ok!(Pallet::<Test>::set_consignor(RuntimeOrigin::signed(1), consigned, Some(7)));
+        assert_ok!(<Balances as Mutate<u64>>::mint_into(&2, 500));
+        let items = || vec![ItemSale { product_id: consigned, amount: 1, discount_percent: None }];
+
+        // As parcelas cobrem apenas os 40 que ficam com a loja
+        assert_noop!(
+            SaleBuilder::new(items(), Date::new(3, 2, 2025).unwrap()).origin(RuntimeOrigin::signed(2)).split(vec![(PaymentMethod::Money, 100)]).call(),
+            Error::<Test>::PaymentMismatch
+        );
+        assert_ok!(SaleBuilder::new(items(), Date::new(3, 2, 2025).unwrap()).origin(RuntimeOrigin::signed(2)).split(vec![(PaymentMethod::Money, 40)]).call());
+        let code = last_registered_sale();
+        assert_eq!(Balances::balance(&2), 440);
+        assert_eq!(Balances::balance(&7), 60);
+
+        // Sem saldo para devolver o repasse, o consignante impede o estorno
+        <Balances as Mutate<u64>>::set_balance(&7, 0);
+        assert_noop!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), code), Error::<Test>::ConsignmentRefundFailed);
+
+        <Balances as Mutate<u64>>::set_balance(&7, 60);
+        assert_ok!(Pallet::<Test>::remove_sale(RuntimeOrigin::signed(1), code));
+        assert_eq!(Balances::balance(&2), 500);
+        assert_eq!(Balances::balance(&7), 0);
+        assert_eq!(SaleConsignments::<Test>::iter_prefix(code).count(), 0);
+    });
+}
+
+#[test]
+fn it_validates_a_cart_without_writing() {
+    new_test_ext().execute_with(|| {
+        let first = add_test_product(10, 50, 5, Category::Misc);
+        let second = add_test_product(5, 30, 5, Category::Food);
+        let next_sale_code = NextSaleCode::<Test>::get();
+
+        assert_ok!(Pallet::<Test>::validate_cart(
+            RuntimeOrigin::signed(2),
+            vec![
+                ItemSale { product_id: first, amount: 2, discount_percent: None },
+                ItemSale { product_id: second, amount: 1, discount_percent: None },
+            ]
+        ));
+
+        System::assert_last_event(Event::CartValid(130).into());
+
+        // Nenhuma venda foi registrada e nenhum estoque foi alterado
+        assert_eq!(NextSaleCode::<Test>::get(), next_sale_code);
+        assert_eq!(Products::<Test>::get(first).unwrap().stock, 10);
+        assert_eq!(Products::<Test>::get(second).unwrap().stock, 5);
+    });
+}
+
+#[test]
+fn it_identifies_the_failing_item_of_an_invalid_cart() {
+    new_test_ext().execute_with(|| {
+        let available = add_test_product(10, 50, 5, Category::Misc);
+        let short = add_test_product(1, 30, 5, Category::Misc);
+
+        assert_ok!(Pallet::<Test>::validate_cart(
+            RuntimeOrigin::signed(2),
+            vec![
+                ItemSale { product_id: available, amount: 2, discount_percent: None },
+                ItemSale { product_id: short, amount: 3, discount_percent: None },
+            ]
+        ));
+
+        System::assert_last_event(Event::CartItemRejected(short, Error::<Test>::InsufficientStock.into()).into());
+        assert_eq!(Products::<Test>::get(available).unwrap().stock, 10);
+    });
+}
+
+#[test]
+fn it_reports_the_requested_and_available_stock_of_a_short_cart_item() {
+    new_test_ext().execute_with(|| {
+        let available = add_test_product(10, 50, 5, Category::Misc);
+        let short = add_test_product(3, 30, 1, Category::Misc);
+
+        // O segundo item do mesmo produto encontra apenas o estoque deixado pelo primeiro
+        assert_ok!(Pallet::<Test>::validate_cart(
+            RuntimeOrigin::signed(2),
+            vec![
+                ItemSale { product_id: available, amount: 2, discount_percent: None },
+                ItemSale { product_id: short, amount: 2, discount_percent: None },
+                ItemSale { product_id: short, amount: 2, discount_percent: None },
+            ]
+        ));
+
+        System::assert_has_event(Event::SaleValidationFailed(short, 2, 1).into());
+        System::assert_last_event(Event::CartItemRejected(short, Error::<Test>::InsufficientStock.into()).into());
+        assert_eq!(Products::<Test>::get(short).unwrap().stock, 3);
+    });
+}
+
+#[test]
+fn it_reports_where_a_cart_total_overflows() {
+    new_test_ext().execute_with(|| {
+        let cheap = add_test_product(100, 10, 1, Category::Misc);
+        let luxury = add_test_product(100, u64::MAX / 2, 1, Category::Misc);
+
+        assert_ok!(Pallet::<Test>::validate_cart(
+            RuntimeOrigin::signed(2),
+            vec![
+                ItemSale { product_id: cheap, amount: 3, discount_percent: None },
+                ItemSale { product_id: luxury, amount: 3, discount_percent: None },
+                ItemSale { product_id: cheap, amount: 1, discount_percent: None },
+            ]
+        ));
+
+        System::assert_has_event(Event::SaleComputationOverflow(luxury, 30).into());
+        System::assert_last_event(Event::CartItemRejected(luxury, Error::<Test>::Overflow.into()).into());
+    });
+}
+
+#[test]
+fn it_splits_the_sale_tax_across_lines_without_residue() {
+    new_test_ext().execute_with(|| {
+        TaxOnSaleTotal::set(true);
+        let large = add_test_product(100, 25, 5, Category::Misc);
+        let first = add_test_product(100, 15, 5, Category::Misc);
+        let second = add_test_product(100, 15, 5, Category::Food);
+        let code = register_test_sale(
+            vec![
+                ItemSale { product_id: first, amount: 1, discount_percent: None },
+                ItemSale { product_id: large, amount: 1, discount_percent: None },
+                ItemSale { product_id: second, amount: 1, discount_percent: None },
+            ],
+            Date::new(3, 2, 2025).unwrap()
+        );
+
+        // 10% sobre o total de 55 dá 5,5, arredondado uma única vez para 6
+        let sale = Sales::<Test>::get(code).unwrap();
+        assert_eq!(sale.tax, 6);
+        // Parcelas proporcionais de 1, 2 e 1, com o resíduo de 2 atribuído ao item de maior valor
+        let taxes: Vec<u64> = sale.lines.iter().map(|line| line.tax).collect();
+        assert_eq!(taxes, vec![1, 4, 1]);
+        assert_eq!(taxes.iter().sum::<u64>(), sale.tax);
+
+        let receipt = Pallet::<Test>::receipt(code).unwrap();
+        assert_eq!(receipt.lines.iter().map(|line| line.tax).sum::<u64>(), receipt.tax);
+    });
+}
+
+#[test]
+fn it_sells_at_the_flash_price_until_it_expires() {
+    new_test_ext().execute_with(|| {
+        use frame_support::traits::Hooks;
+
+        let id = add_test_product(100, 50, 5, Category::Misc);
+        System::set_block_number(5);
+
+        assert_noop!(
+            Pallet::<Test>::set_flash_price(RuntimeOrigin::signed(1), id, 30, 4),
+            Error::<Test>::FlashPriceExpired
+        );
+        assert_ok!(Pallet::<Test>::set_flash_price(RuntimeOrigin::signed(1), id, 30, 10));
+        System::assert_last_event(Event::FlashPriceSet(id, 30, 10).into());
+
+        // O preço base não é alterado
+        assert_eq!(Products::<Test>::get(id).unwrap().price, 50);
+
+        let date = Date::new(3, 2, 2025).unwrap();
+        System::set_block_number(10);
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], date.clone());
+        assert_eq!(Sales::<Test>::get(code).unwrap().value, 60);
+
+        // Após o último bloco, a venda volta ao preço base e a oferta é removida
+        System::set_block_number(11);
+        let code = register_test_sale(vec![ItemSale { product_id: id, amount: 2, discount_percent: None }], date);
+        assert_eq!(Sales::<Test>::get(code).unwrap().value, 100);
+
+        Pallet::<Test>::on_initialize(11);
+        assert!(!FlashPrices::<Test>::contains_key(id));
+        assert!(FlashExpiries::<Test>::get(11).is_empty());
+    });
+}
+
+#[test]
+fn it_expires_flash_prices_by_their_end_block() {
+    new_test_ext().execute_with(|| {
+        let first = add_test_product(100, 50, 5, Category::Misc);
+        let second = add_test_product(100, 50, 5, Category::Misc);
+        let third = add_test_product(100, 50, 5, Category::Misc);
+
+        assert_ok!(Pallet::<Test>::set_flash_price(RuntimeOrigin::signed(1), first, 30, 5));
+        assert_ok!(Pallet::<Test>::set_flash_price(RuntimeOrigin::signed(1), second, 30, 5));
+        assert_noop!(
+            Pallet::<Test>::set_flash_price(RuntimeOrigin::signed(1), third, 30, 5),
+            Error::<Test>::TooManyFlashExpiries
+        );
+
+        // A oferta estendida continua valendo após o fim da oferta anterior
+        assert_ok!(Pallet::<Test>::set_flash_price(RuntimeOrigin::signed(1), second, 20, 8));
+        assert_eq!(FlashExpiries::<Test>::get(6).into_inner(), vec![first, second]);
+
+        run_to_block(6);
+        assert!(!FlashPrices::<Test>::contains_key(first));
+        assert_eq!(FlashPrices::<Test>::get(second), Some((20, 8)));
+
+        run_to_block(9);
+        assert!(!FlashPrices::<Test>::contains_key(second));
+    });
+}
+
+#[test]
+fn it_records_the_stock_unit_of_a_product() {
+    new_test_ext().execute_with(|| {
+        let piece = add_test_product(10, 50, 5, Category::Misc);
+        assert_eq!(Pallet::<Test>::stock_unit_label(piece), Some(StockUnitLabel::Piece));
+
+        let milk = ProductBuilder::new(10, 5, 1, Category::Food).name(b"Milk".to_vec()).unit(StockUnitLabel::Liter).add();
+        assert_eq!(Products::<Test>::get(milk).unwrap().unit, StockUnitLabel::Liter);
+
+        assert_ok!(ProductUpdate::new(milk).unit(StockUnitLabel::Kilogram).call());
+        assert_eq!(Pallet::<Test>::stock_unit_label(milk), Some(StockUnitLabel::Kilogram));
+        assert_eq!(Pallet::<Test>::stock_unit_label(999), None);
+    });
+}
+
+#[test]
+fn it_rejects_fractional_quantities_of_pieces() {
+    new_test_ext().execute_with(|| {
+        StockUnit::set(1_000);
+        let id = add_test_product(5_000, 200, 1_000, Category::Misc);
+
+        assert_noop!(
+            SaleBuilder::new(vec![ItemSale { product_id: id, amount: 1_500, discount_percent: None }], Date::new(3, 2, 2025).unwrap()).call(),
+            Error::<Test>::FractionalQuantity
+        );
+        assert_noop!(
+            ProductUpdate::new(id).stock(2_500).call(),
+            Error::<Test>::FractionalQuantity
+        );
+
+        register_test_sale(vec![ItemSale { product_id: id, amount: 2_000, discount_percent: None }], Date::new(3, 2, 2025).unwrap());
+        assert_eq!(Products::<Test>::get(id).unwrap().stock, 3_000);
+    });
+}
+
+#[test]
+fn it_revalues_a_sale_at_current_prices() {
+    new_test_ext().execute_with(|| {
+        let pen = add_test_product(100, 10, 5, Category::Misc);
+        let book = add_test_product(100, 25, 5, Category::Misc);
+        let code = register_test_sale(
+            vec![
+                ItemSale { product_id: pen, amount: 3, discount_percent: Some(10) },
+                ItemSale { product_id: book, amount: 2, discount_percent: None },
+            ],
+            Date::new(3, 2, 2025).unwrap()
+        );
+        assert_eq!(Sales::<Test>::get(code).unwrap().value, 77);
+
+        assert_ok!(ProductUpdate::new(book).price(40).call());
+
+        // Preços atuais, sem descontos: 3 * 10 + 2 * 40
+        assert_eq!(Pallet::<Test>::revalue_sale(code), Some(110));
+        // O valor registrado da venda não é alterado
+        assert_eq!(Sales::<Test>::get(code).unwrap().value, 77);
+
+        // A sobretaxa vigente do método de pagamento entra no valor, como no total registrado
+        assert_ok!(Pallet::<Test>::set_payment_surcharge(RuntimeOrigin::root(), PaymentMethod::Credit, Some(1_000)));
+        assert_eq!(Pallet::<Test>::revalue_sale(code), Some(121));
+
+        assert_eq!(Pallet::<Test>::revalue_sale(999), None);
+    });
+}
+
+#[test]
+fn it_clamps_discounted_prices_to_the_price_floor() {
+    new_test_ext().execute_with(|| {
+        let id = add_test_product(100, 100, 5, Category::Misc);
+
+        assert_noop!(
+            Pallet::<Test>::set_price_floor(RuntimeOrigin::signed(1), id, Some(150)),
+            Error::<Test>::InvalidPriceFloor
+        );
+        assert_ok!(Pallet::<Test>::set_price_floor(RuntimeOrigin::signed(1), id, Some(80)));
+        System::assert_last_event(Event::PriceFloorSet(id, Some(80)).into());
+
+        // Um desconto de 50% levaria o item a 100, abaixo do piso de 2 * 80
+        let code = register_test_sale(
+            vec![ItemSale { product_id: id, amount: 2, discount_percent: Some(50) }],
+            Date::new(3, 2, 2025).unwrap()
+        );
+        System::assert_has_event(Event::PriceFloored(id).into());
+        assert_eq!(Sales::<Test>::get(code).unwrap().value, 160);
+
+        // Descontos que respeitam o piso não são alterados
+        let code = register_test_sale(
+            vec![ItemSale { product_id: id, amount: 2, discount_percent: Some(10) }],
+            Date::new(3, 2, 2025).unwrap()
+        );
+        assert_eq!(Sales::<Test>::get(code).unwrap().value, 180);
+    });
+}
+
+#[test]
+fn it_transfers_the_whole_catalog_in_pages() {
+    new_test_ext().execute_with(|| {
+        let ids: Vec<u64> = (0..12).map(|_| add_test_product(10, 50, 5, Category::Misc)).collect();
+
+        assert_noop!(Pallet::<Test>::transfer_all_products(RuntimeOrigin::signed(1), 3), sp_runtime::DispatchError::BadOrigin);
+
+        // Cada chamada percorre no máximo `MaxPageSize` (10) produtos
+        assert_ok!(Pallet::<Test>::transfer_all_products(RuntimeOrigin::root(), 3));
+        System::assert_last_event(Event::CatalogTransferred(3, 10).into());
+        assert_eq!(TransferCursor::<Test>::get().map(|(owner, _)| owner), Some(3));
+
+        // A transferência em andamento só continua para o mesmo destino
+        assert_noop!(Pallet::<Test>::transfer_all_products(RuntimeOrigin::root(), 4), Error::<Test>::TransferInProgress);
+
+        assert_ok!(Pallet::<Test>::transfer_all_products(RuntimeOrigin::root(), 3));
+        System::assert_last_event(Event::CatalogTransferred(3, 2).into());
+        assert!(TransferCursor::<Test>::get().is_none());
+
+        assert_eq!(OwnerProducts::<Test>::iter_prefix(1).count(), 0);
+        assert_eq!(OwnerProducts::<Test>::iter_prefix(3).count(), 12);
+
+        // Apenas o novo responsável pode transferir cada produto
+        for id in ids {
+            assert_eq!(Pallet::<Test>::product_owner(id), Some(3));
+            assert_noop!(Pallet::<Test>::transfer_product(RuntimeOrigin::signed(1), id, 1), Error::<Test>::NotProductOwner);
+            assert_ok!(Pallet::<Test>::transfer_product(RuntimeOrigin::signed(3), id, 1));
+        }
+    });
+}
+
+#[test]
+fn it_transfers_an_empty_catalog_without_bumping_the_version() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Pallet::<Test>::transfer_all_products(RuntimeOrigin::root(), 3));
+        System::assert_last_event(Event::CatalogTransferred(3, 0).into());
+        assert_eq!(Pallet::<Test>::catalog_version(), 0);
+
+        assert_ok!(Pallet::<Test>::set_paused(RuntimeOrigin::root(), true));
+        assert_noop!(Pallet::<Test>::transfer_all_products(RuntimeOrigin::root(), 3), Error::<Test>::Paused);
+    });
+}
+
+#[test]
+fn it_migrates_v0_products_and_sales() {
+    use crate::migrations::{v0, MigrateV0ToV1};
+    use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<Pallet<Test>>();
+
+        let date = Date::new(1, 1, 2023).unwrap();
+        let old_product = v0::OldProduct {
+            name: b"Old Product".to_vec(),
+            id: 0,
+            stock: 10,
+            price: 100,
+            amount_to_restock: 5,
+            restock_date: date.clone(),
+            category: Category::Food
+        };
+        let old_sale = v0::OldSale {
+            seller: b"Seller".to_vec(),
+            code: 0,
+            products: vec![0],
+            value: 300,
+            date: date.clone(),
+            payment_method: PaymentMethod::Money
+        };
+        frame_support::storage::unhashed::put(&Products::<Test>::hashed_key_for(0), &old_product);
+        frame_support::storage::unhashed::put(&Sales::<Test>::hashed_key_for(0), &old_sale);
+
+        MigrateV0ToV1::<Test>::on_runtime_upgrade();
+        assert_eq!(Pallet::<Test>::on_chain_storage_version(), StorageVersion::new(1));
+
+        let product = Products::<Test>::get(0).unwrap();
+        assert_eq!(product.name, b"Old Product".to_vec());
+        assert_eq!(product.id, 0);
+        assert_eq!(product.stock, 10);
+        assert_eq!(product.reorder_point, 5);
+        assert_eq!(product.unit, StockUnitLabel::Piece);
+        assert_eq!(Products::<Test>::count(), 1);
+        assert!(ProductsByCategory::<Test>::contains_key(Category::Food, 0));
+        assert_eq!(CategoryCounts::<Test>::get(Category::Food), 1);
+        assert_eq!(CategoryStockTotal::<Test>::get(Category::Food), 10);
+
+        let sale = Sales::<Test>::get(0).unwrap();
+        assert_eq!(sale.code, 0);
+        assert_eq!(sale.value, 300);
+        assert_eq!(sale.net, 300);
+        assert_eq!(sale.payments, vec![(PaymentMethod::Money, 300)]);
+        assert_eq!(Sales::<Test>::count(), 1);
+        assert_eq!(DailyRevenue::<Test>::get(&date), 300);
+        assert_eq!(TotalSalesValue::<Test>::get(), 300);
+        assert_eq!(SaleCount::<Test>::get(), 1);
+
+        // Com a versão já atualizada, a migração não é executada de novo
+        MigrateV0ToV1::<Test>::on_runtime_upgrade();
+        assert_eq!(TotalSalesValue::<Test>::get(), 300);
+    });
+}
+
+// Adiciona um produto e o trava, retornando o ID gerado.
+fn add_locked_test_product() -> u64 {
+    let id = add_test_product(100, 50, 5, Category::Misc);
+    assert_ok!(Pallet::<Test>::lock_product(RuntimeOrigin::root(), id));
+    id
+}
+
+#[test]
+fn it_refuses_to_set_the_tax_category_of_a_locked_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_locked_test_product();
+        assert_noop!(Pallet::<Test>::set_product_tax_category(RuntimeOrigin::signed(1), id, 1), Error::<Test>::ProductLocked);
+    });
+}
+
+#[test]
+fn it_refuses_to_set_the_threshold_mode_of_a_locked_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_locked_test_product();
+        assert_noop!(Pallet::<Test>::set_threshold_mode(RuntimeOrigin::signed(1), id, ThresholdMode::DaysOfCover), Error::<Test>::ProductLocked);
+    });
+}
+
+#[test]
+fn it_refuses_to_set_the_reorder_point_of_a_locked_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_locked_test_product();
+        assert_noop!(Pallet::<Test>::set_reorder_point(RuntimeOrigin::signed(1), id, 10), Error::<Test>::ProductLocked);
+    });
+}
+
+#[test]
+fn it_refuses_to_set_the_member_price_of_a_locked_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_locked_test_product();
+        assert_noop!(Pallet::<Test>::set_member_price(RuntimeOrigin::signed(1), id, Some(40)), Error::<Test>::ProductLocked);
+    });
+}
+
+#[test]
+fn it_refuses_to_set_a_promo_on_a_locked_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_locked_test_product();
+        let promo = (Date::new(1, 2, 2025).unwrap(), Date::new(28, 2, 2025).unwrap(), 10);
+        assert_noop!(Pallet::<Test>::set_promo(RuntimeOrigin::signed(1), id, Some(promo)), Error::<Test>::ProductLocked);
+    });
+}
+
+#[test]
+fn it_refuses_to_set_the_cost_price_of_a_locked_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_locked_test_product();
+        assert_noop!(Pallet::<Test>::set_cost_price(RuntimeOrigin::signed(1), id, Some(30)), Error::<Test>::ProductLocked);
+    });
+}
+
+#[test]
+fn it_refuses_to_set_a_flash_price_on_a_locked_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_locked_test_product();
+        assert_noop!(Pallet::<Test>::set_flash_price(RuntimeOrigin::signed(1), id, 40, 10), Error::<Test>::ProductLocked);
+    });
+}
+
+#[test]
+fn it_refuses_to_set_the_price_floor_of_a_locked_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_locked_test_product();
+        assert_noop!(Pallet::<Test>::set_price_floor(RuntimeOrigin::signed(1), id, Some(40)), Error::<Test>::ProductLocked);
+    });
+}
+
+#[test]
+fn it_refuses_to_set_the_consignor_of_a_locked_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_locked_test_product();
+        assert_noop!(Pallet::<Test>::set_consignor(RuntimeOrigin::signed(1), id, Some(7)), Error::<Test>::ProductLocked);
+    });
+}
+
+#[test]
+fn it_refuses_to_transfer_a_locked_product() {
+    new_test_ext().execute_with(|| {
+        let id = add_locked_test_product();
+        assert_noop!(Pallet::<Test>::transfer_product(RuntimeOrigin::signed(1), id, 3), Error::<Test>::ProductLocked);
+    });
+}
+
+#[test]
+fn it_skips_locked_products_when_archiving_below_stock() {
+    new_test_ext().execute_with(|| {
+        let locked = add_locked_test_product();
+        let unlocked = add_test_product(100, 50, 5, Category::Misc);
+
+        assert_ok!(Pallet::<Test>::archive_below_stock(RuntimeOrigin::root(), 100));
+        System::assert_last_event(Event::BulkArchived(1).into());
+        assert!(Products::<Test>::contains_key(locked));
+        assert!(!Products::<Test>::contains_key(unlocked));
+    });
+}
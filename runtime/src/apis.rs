@@ -42,8 +42,9 @@ use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Aura, Balance, Block, Executive, Grandpa, InherentDataExt, Nonce, Runtime,
-	RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment, VERSION,
+	AccountId, Aura, Balance, Block, BlockNumber, Executive, Grandpa, InherentDataExt, Nonce, Runtime,
+	RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TemplateModule, TransactionPayment,
+	VERSION,
 };
 
 impl_runtime_apis! {
@@ -170,6 +171,71 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_template::runtime_api::StoreApi<Block, BlockNumber, pallet_template::ProductOf<Runtime>> for Runtime {
+		fn is_sellable(id: u64, amount: u64) -> bool {
+			TemplateModule::is_sellable(id, amount)
+		}
+
+		fn cheapest_in_category(category: pallet_template::Category, in_stock_only: bool) -> Option<(u64, u64)> {
+			TemplateModule::cheapest_in_category(category, in_stock_only)
+		}
+
+		fn sales_in_block_range(from: BlockNumber, to: BlockNumber) -> Vec<u64> {
+			TemplateModule::sales_in_block_range(from, to)
+		}
+
+		fn highest_value_product() -> Option<(u64, u64)> {
+			TemplateModule::highest_value_product()
+		}
+
+		fn export_snapshot() -> (Vec<pallet_template::ProductOf<Runtime>>, Vec<pallet_template::Sale>) {
+			TemplateModule::export_snapshot()
+		}
+
+		fn products_page(
+			cursor: Option<pallet_template::StorageCursor>,
+			limit: u32,
+		) -> (Vec<(u64, pallet_template::ProductOf<Runtime>)>, Option<pallet_template::StorageCursor>) {
+			TemplateModule::products_page(cursor, limit)
+		}
+
+		fn store_stats() -> pallet_template::StoreStats {
+			TemplateModule::store_stats()
+		}
+
+		fn average_sale_value() -> u64 {
+			TemplateModule::average_sale_value()
+		}
+
+		fn used_categories() -> Vec<pallet_template::Category> {
+			TemplateModule::used_categories()
+		}
+
+		fn product_exists(id: u64) -> bool {
+			TemplateModule::product_exists(id)
+		}
+
+		fn sale_exists(code: u64) -> bool {
+			TemplateModule::sale_exists(code)
+		}
+
+		fn sales_velocity(id: u64) -> u64 {
+			TemplateModule::sales_velocity(id)
+		}
+
+		fn product_encoded_size(id: u64) -> Option<u32> {
+			TemplateModule::product_encoded_size(id)
+		}
+
+		fn receipt(code: u64) -> Option<pallet_template::Receipt> {
+			TemplateModule::receipt(code)
+		}
+
+		fn revalue_sale(code: u64) -> Option<u64> {
+			TemplateModule::revalue_sale(code)
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
 		fn query_info(
 			uxt: <Block as BlockT>::Extrinsic,
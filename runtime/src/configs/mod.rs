@@ -42,7 +42,7 @@ use sp_version::RuntimeVersion;
 use super::{
 	AccountId, Aura, Balance, Balances, Block, BlockNumber, Hash, Nonce, PalletInfo, Runtime,
 	RuntimeCall, RuntimeEvent, RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask,
-	System, EXISTENTIAL_DEPOSIT, SLOT_DURATION, VERSION,
+	StaffMembership, System, DAYS, EXISTENTIAL_DEPOSIT, HOURS, SLOT_DURATION, VERSION,
 };
 
 const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
@@ -140,6 +140,10 @@ parameter_types! {
 	pub FeeMultiplier: Multiplier = Multiplier::one();
 }
 
+parameter_types! {
+	pub const StoreRounding: pallet_template::RoundingMode = pallet_template::RoundingMode::Nearest;
+}
+
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type OnChargeTransaction = FungibleAdapter<Balances, ()>;
@@ -155,8 +159,70 @@ impl pallet_sudo::Config for Runtime {
 	type WeightInfo = pallet_sudo::weights::SubstrateWeight<Runtime>;
 }
 
+/// Store staff, managed by root, used as the pallet-template `StaffOrigin`.
+impl pallet_membership::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AddOrigin = frame_system::EnsureRoot<AccountId>;
+	type RemoveOrigin = frame_system::EnsureRoot<AccountId>;
+	type SwapOrigin = frame_system::EnsureRoot<AccountId>;
+	type ResetOrigin = frame_system::EnsureRoot<AccountId>;
+	type PrimeOrigin = frame_system::EnsureRoot<AccountId>;
+	type MembershipInitialized = ();
+	type MembershipChanged = ();
+	type MaxMembers = ConstU32<100>;
+	type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
+	type MaxRestockAmount = ConstU64<1_000_000>;
+	type EmitListingPayloads = ConstBool<true>;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type StaffOrigin = frame_system::EnsureSignedBy<StaffMembership, AccountId>;
+	type CustomerOrigin = frame_system::EnsureSigned<AccountId>;
+	type MaxPriceHistory = ConstU32<32>;
+	type PriceHistoryMaxAge = ConstU32<{ 90 * DAYS }>;
+	type MaxPageSize = ConstU32<100>;
+	type RecycleIds = ConstBool<false>;
+	type MaxFreeProductIds = ConstU32<100>;
+	type MaxSalesPerBlock = ConstU32<500>;
+	type Rounding = StoreRounding;
+	type MaxBundleSize = ConstU32<16>;
+	type CommissionBasisPoints = ConstU32<500>;
+	type MinYear = ConstU64<2000>;
+	type MaxYear = ConstU64<2100>;
+	type MaxSnapshotSize = ConstU32<1_000>;
+	type AllowOversell = ConstBool<false>;
+	type DefaultTaxRate = ConstU32<0>;
+	type HashedSaleCodes = ConstBool<false>;
+	type RestockSlaBlocks = ConstU32<{ 7 * DAYS }>;
+	type MaxSlaChecksPerBlock = ConstU32<50>;
+	type DefaultPageSize = ConstU32<20>;
+	type BlocksPerDay = ConstU32<DAYS>;
+	type PriceIncrement = ConstU64<1>;
+	type Currency = Balances;
+	type SaleFee = ConstU128<0>;
+	type FeeDestination = ();
+	type StockUnit = ConstU64<1>;
+	type TimeProvider = pallet_timestamp::Pallet<Runtime>;
+	type EnforceFutureRestock = ConstBool<false>;
+	type SaleRetentionBlocks = ConstU32<{ 365 * DAYS }>;
+	type MaxSalesPrunedPerBlock = ConstU32<50>;
+	type BulkQtyThreshold = ConstU64<0>;
+	type IdStep = ConstU64<1>;
+	type UndoWindow = ConstU32<DAYS>;
+	type SaleEditWindow = ConstU32<HOURS>;
+	type RejectEmptyUpdates = ConstBool<false>;
+	type MinMarginBasisPoints = ConstU32<0>;
+	type VelocityWindow = ConstU32<{ 7 * DAYS }>;
+	type MaxVelocitySamples = ConstU32<128>;
+	type RefundWindowBlocks = ConstU32<0>;
+	type DeletionApprovalThreshold = ConstU64<0>;
+	type TaxOnSaleTotal = ConstBool<false>;
+	type MinListableStock = ConstU64<0>;
+	type MaxAttributes = ConstU32<16>;
+	type MaxFlashExpiries = ConstU32<256>;
+	type MaxRecentSalesScan = ConstU32<1_000>;
 }